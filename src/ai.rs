@@ -0,0 +1,285 @@
+use std::f32::consts::TAU;
+
+use bevy::{
+    app::{Plugin, PostUpdate, Update},
+    ecs::{
+        change_detection::DetectChanges,
+        component::Component,
+        query::{Added, With},
+        removal_detection::RemovedComponents,
+        schedule::{common_conditions::in_state, Condition, IntoSystemConfigs},
+        system::{Commands, Query, Res},
+    },
+    math::{IVec2, Vec2},
+    time::{Time, Timer, TimerMode},
+    transform::{
+        components::{GlobalTransform, Transform},
+        TransformSystem,
+    },
+};
+use rand::Rng;
+
+mod pathfinding;
+
+use pathfinding::NavGrid;
+
+use crate::{
+    characters::{CharacterState, Player, Status},
+    moveable::{move_characters, Moveable},
+    pause::PauseState,
+    tile_collision::{feet_position, Collider},
+    GameState,
+};
+
+/// A patrolling character moves on to its next waypoint once it is this
+/// close to the current one, in pixels.
+const WAYPOINT_REACHED_DISTANCE: f32 = 4.;
+
+/// Shortest and longest time a wandering character keeps going in the same
+/// direction.
+const WANDER_SECONDS_MIN: f32 = 1.;
+const WANDER_SECONDS_MAX: f32 = 3.;
+
+/// Chance that a wandering character stands still rather than picking a new
+/// direction.
+const WANDER_PAUSE_CHANCE: f64 = 0.25;
+
+/// A character walking through the waypoints in order, going back to the
+/// first after the last. Waypoints are positions in the world.
+#[derive(Component)]
+pub(crate) struct Patrol {
+    waypoints: Vec<Vec2>,
+    next: usize,
+    /// Position at the last frame, to tell when the character is blocked.
+    last_position: Option<Vec2>,
+}
+
+impl Patrol {
+    pub(crate) fn new(waypoints: Vec<Vec2>) -> Self {
+        Self {
+            waypoints,
+            next: 0,
+            last_position: None,
+        }
+    }
+}
+
+/// A character walking in a random direction, picking a new direction when
+/// the timer finishes.
+#[derive(Component)]
+pub(crate) struct Wander(Timer);
+
+impl Default for Wander {
+    /// Picks a direction on the first frame.
+    fn default() -> Self {
+        Self(Timer::from_seconds(0., TimerMode::Once))
+    }
+}
+
+/// A character walking towards the nearest player within its sight radius,
+/// in pixels, along the shortest path around the colliders. It stands still
+/// when no player is in sight.
+#[derive(Component)]
+pub(crate) struct Chase {
+    sight_radius: f32,
+    /// Centres of the cells left to walk through to reach the player.
+    path: Vec<Vec2>,
+    /// The [`NavGrid`] cell of the player when the path was found, the path
+    /// is only found again once the player leaves it.
+    target_cell: Option<IVec2>,
+}
+
+impl Chase {
+    pub(crate) fn new(sight_radius: f32) -> Self {
+        Self {
+            sight_radius,
+            path: Vec::new(),
+            target_cell: None,
+        }
+    }
+}
+
+/// A character running away from the nearest player within its sight
+/// radius, in pixels. It stands still when no player is in sight.
+#[derive(Component)]
+pub(crate) struct Flee {
+    pub(crate) sight_radius: f32,
+}
+
+/// Plugin moving the characters with a [`Patrol`], [`Wander`], [`Chase`] or
+/// [`Flee`] behaviour. Behaviours only set the [`Moveable`] direction, so
+/// these characters are moved by [`crate::moveable::Movement`] and animated
+/// like the players.
+pub(crate) struct Ai;
+
+impl Plugin for Ai {
+    fn build(&self, app: &mut bevy::prelude::App) {
+        app.add_systems(
+            Update,
+            // After the movement, so that a patrolling character that didn't
+            // move this frame is known to be blocked
+            (patrol.after(move_characters), wander, chase, flee)
+                .run_if(in_state(GameState::Game).and_then(in_state(PauseState::Running))),
+        )
+        .add_systems(
+            PostUpdate,
+            build_nav_grid.after(TransformSystem::TransformPropagate),
+        );
+    }
+}
+
+/// Returns the position of the nearest player still alive within
+/// `sight_radius` of `position`, from the positions of the players and their
+/// [`Status`].
+pub(crate) fn nearest_player_in_sight<'a>(
+    position: Vec2,
+    sight_radius: f32,
+    players: impl IntoIterator<Item = (Vec2, &'a Status)>,
+) -> Option<Vec2> {
+    players
+        .into_iter()
+        .filter(|(_, status)| status.state() == CharacterState::Alive)
+        .map(|(player, _)| player)
+        .filter(|player| player.distance(position) <= sight_radius)
+        .min_by(|a, b| a.distance(position).total_cmp(&b.distance(position)))
+}
+
+/// Returns the feet of the players with their [`Status`], for
+/// [`nearest_player_in_sight`].
+fn player_feet<'a>(
+    player_query: &'a Query<(&Transform, &Status), With<Player>>,
+) -> Vec<(Vec2, &'a Status)> {
+    player_query
+        .iter()
+        .map(|(transform, status)| (feet_position(transform.translation), status))
+        .collect()
+}
+
+/// Walk the patrolling characters towards their next waypoint, moving on to
+/// the following one once they reach it or when they haven't moved since the
+/// last frame.
+fn patrol(mut query: Query<(&Transform, &mut Moveable, &mut Patrol)>) {
+    for (transform, mut moveable, mut patrol) in &mut query {
+        let position = transform.translation.truncate();
+        let Some(&waypoint) = patrol.waypoints.get(patrol.next) else {
+            continue;
+        };
+
+        let blocked = patrol.last_position == Some(position) && moveable.direction() != Vec2::ZERO;
+        if blocked || position.distance(waypoint) <= WAYPOINT_REACHED_DISTANCE {
+            patrol.next = (patrol.next + 1) % patrol.waypoints.len();
+        }
+
+        let waypoint = patrol.waypoints[patrol.next];
+        moveable.set_direction((waypoint - position).normalize_or_zero());
+
+        patrol.last_position = Some(position);
+    }
+}
+
+/// Pick a new random direction for the wandering characters whose timer has
+/// finished, sometimes standing still instead.
+fn wander(time: Res<Time>, mut query: Query<(&mut Moveable, &mut Wander)>) {
+    let mut rng = rand::thread_rng();
+
+    for (mut moveable, mut wander) in &mut query {
+        wander.0.tick(time.delta());
+        if !wander.0.finished() {
+            continue;
+        }
+
+        let direction = if rng.gen_bool(WANDER_PAUSE_CHANCE) {
+            Vec2::ZERO
+        } else {
+            Vec2::from_angle(rng.gen_range(0. ..TAU))
+        };
+        moveable.set_direction(direction);
+
+        wander.0 = Timer::from_seconds(
+            rng.gen_range(WANDER_SECONDS_MIN..WANDER_SECONDS_MAX),
+            TimerMode::Once,
+        );
+    }
+}
+
+/// Build the [`NavGrid`] from the colliders of the level whenever they change.
+/// Colliders are only positioned once their transforms have been propagated.
+fn build_nav_grid(
+    mut commands: Commands,
+    added_query: Query<(), Added<Collider>>,
+    mut removed_colliders: RemovedComponents<Collider>,
+    collider_query: Query<&GlobalTransform, With<Collider>>,
+) {
+    if added_query.is_empty() && removed_colliders.iter().count() == 0 {
+        return;
+    }
+
+    commands.insert_resource(NavGrid::new(
+        collider_query
+            .iter()
+            .map(|transform| transform.translation().truncate()),
+    ));
+}
+
+/// Walk the chasing characters towards the nearest player in sight, heading
+/// for the next cell of the path to them found on the [`NavGrid`]. The path
+/// is only found again when the player moves to another cell or the grid
+/// changes. They head straight for the player once in the same cell, or if
+/// no path is found.
+fn chase(
+    mut query: Query<(&Transform, &mut Moveable, &mut Chase)>,
+    player_query: Query<(&Transform, &Status), With<Player>>,
+    nav_grid: Option<Res<NavGrid>>,
+) {
+    let Some(nav_grid) = nav_grid else {
+        return;
+    };
+    let players = player_feet(&player_query);
+
+    for (transform, mut moveable, mut chase) in &mut query {
+        let feet = feet_position(transform.translation);
+        let Some(player) =
+            nearest_player_in_sight(feet, chase.sight_radius, players.iter().copied())
+        else {
+            chase.path.clear();
+            chase.target_cell = None;
+            moveable.set_direction(Vec2::ZERO);
+            continue;
+        };
+
+        let target_cell = NavGrid::cell(player);
+        if chase.target_cell != Some(target_cell) || nav_grid.is_changed() {
+            chase.path = nav_grid.find_path(feet, player).unwrap_or_default();
+            chase.target_cell = Some(target_cell);
+        }
+
+        // Drop the cells of the path already walked through
+        let cell = NavGrid::cell(feet);
+        if let Some(reached) = chase
+            .path
+            .iter()
+            .position(|center| NavGrid::cell(*center) == cell)
+        {
+            chase.path.drain(..=reached);
+        }
+
+        let next = chase.path.first().copied().unwrap_or(player);
+        moveable.set_direction((next - feet).normalize_or_zero());
+    }
+}
+
+/// Walk the fleeing characters straight away from the nearest player in
+/// sight.
+fn flee(
+    mut query: Query<(&Transform, &mut Moveable, &Flee)>,
+    player_query: Query<(&Transform, &Status), With<Player>>,
+) {
+    let players = player_feet(&player_query);
+
+    for (transform, mut moveable, flee) in &mut query {
+        let feet = feet_position(transform.translation);
+        let direction = nearest_player_in_sight(feet, flee.sight_radius, players.iter().copied())
+            .map_or(Vec2::ZERO, |player| (feet - player).normalize_or_zero());
+        moveable.set_direction(direction);
+    }
+}
@@ -0,0 +1,148 @@
+use std::{cmp::Reverse, collections::BinaryHeap};
+
+use bevy::{
+    ecs::system::Resource,
+    math::{IVec2, Vec2},
+    utils::{HashMap, HashSet},
+};
+
+use crate::{
+    setup::{
+        CHARACTER_BOTTOM_BOUND, CHARACTER_LEFT_BOUND, CHARACTER_RIGHT_BOUND, CHARACTER_TOP_BOUND,
+    },
+    tile_collision::TILE_SIZE,
+};
+
+/// Steps between neighbouring cells. Characters only walk between cells
+/// sharing a side, so paths never cut the corner of a collider.
+const STEPS: [IVec2; 4] = [IVec2::X, IVec2::NEG_X, IVec2::Y, IVec2::NEG_Y];
+
+/// The cells of the `Collision` IntGrid layer, telling which ones characters
+/// can walk through. Cells are [`TILE_SIZE`] squares counted from the world
+/// origin, which lines up with the layer of levels centred on the screen.
+/// It is rebuilt whenever the colliders of the level change.
+#[derive(Resource)]
+pub(crate) struct NavGrid {
+    blocked: HashSet<IVec2>,
+    min: IVec2,
+    max: IVec2,
+}
+
+impl NavGrid {
+    /// Creates a [`NavGrid`] covering the area characters can walk in, with
+    /// the cells of the colliders at the given positions blocked.
+    pub(crate) fn new(colliders: impl IntoIterator<Item = Vec2>) -> Self {
+        Self {
+            blocked: colliders.into_iter().map(Self::cell).collect(),
+            min: Self::cell(Vec2::new(CHARACTER_LEFT_BOUND, CHARACTER_BOTTOM_BOUND)),
+            max: Self::cell(Vec2::new(CHARACTER_RIGHT_BOUND, CHARACTER_TOP_BOUND)),
+        }
+    }
+
+    /// Returns the cell containing the position.
+    pub(crate) fn cell(position: Vec2) -> IVec2 {
+        (position / TILE_SIZE).floor().as_ivec2()
+    }
+
+    /// Returns the position of the centre of the cell.
+    fn cell_center(cell: IVec2) -> Vec2 {
+        (cell.as_vec2() + 0.5) * TILE_SIZE
+    }
+
+    fn walkable(&self, cell: IVec2) -> bool {
+        cell.cmpge(self.min).all() && cell.cmple(self.max).all() && !self.blocked.contains(&cell)
+    }
+
+    /// Find the shortest path from `from` to `to` around the blocked cells,
+    /// with the A* algorithm. Returns the centres of the cells to walk
+    /// through, the cell `to` is in being last and the one `from` is in left
+    /// out, or `None` when `to` can't be reached.
+    pub(crate) fn find_path(&self, from: Vec2, to: Vec2) -> Option<Vec<Vec2>> {
+        let start = Self::cell(from);
+        let goal = Self::cell(to);
+        if !self.walkable(goal) {
+            return None;
+        }
+
+        // Cells are ordered by the cost of the path through them plus the
+        // Manhattan distance left to the goal, which never overestimates the
+        // cost when walking between cells sharing a side
+        let estimate = |cell: IVec2, cost: i32| {
+            let distance = (goal - cell).abs();
+            cost + distance.x + distance.y
+        };
+
+        let mut open = BinaryHeap::from([Reverse((estimate(start, 0), start.x, start.y))]);
+        let mut costs = HashMap::from_iter([(start, 0)]);
+        let mut came_from = HashMap::<IVec2, IVec2>::new();
+
+        while let Some(Reverse((_, x, y))) = open.pop() {
+            let cell = IVec2::new(x, y);
+
+            if cell == goal {
+                let mut path = Vec::new();
+                let mut current = goal;
+                while current != start {
+                    path.push(Self::cell_center(current));
+                    current = came_from[&current];
+                }
+                path.reverse();
+                return Some(path);
+            }
+
+            let cost = costs[&cell] + 1;
+            for neighbour in STEPS.map(|step| cell + step) {
+                if !self.walkable(neighbour)
+                    || costs.get(&neighbour).is_some_and(|known| *known <= cost)
+                {
+                    continue;
+                }
+
+                costs.insert(neighbour, cost);
+                came_from.insert(neighbour, cell);
+                open.push(Reverse((
+                    estimate(neighbour, cost),
+                    neighbour.x,
+                    neighbour.y,
+                )));
+            }
+        }
+
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Returns the position of the centre of the cell at `x`, `y`.
+    fn at(x: i32, y: i32) -> Vec2 {
+        NavGrid::cell_center(IVec2::new(x, y))
+    }
+
+    #[test]
+    fn find_path_to_blocked_goal() {
+        let nav_grid = NavGrid::new([at(2, 0)]);
+
+        assert_eq!(nav_grid.find_path(at(-2, 0), at(2, 0)), None);
+    }
+
+    #[test]
+    fn find_path_around_wall() {
+        let wall = [at(0, -1), at(0, 0), at(0, 1)];
+        let nav_grid = NavGrid::new(wall);
+
+        let path = nav_grid.find_path(at(-2, 0), at(2, 0)).unwrap();
+
+        // Up or down two cells, across four and back two
+        assert_eq!(path.len(), 8);
+        assert_eq!(path.last(), Some(&at(2, 0)));
+        assert!(path.iter().all(|cell| !wall.contains(cell)));
+        assert!(std::iter::once(at(-2, 0))
+            .chain(path.iter().copied())
+            .collect::<Vec<_>>()
+            .windows(2)
+            .all(|step| step[0].distance(step[1]) == TILE_SIZE));
+    }
+}
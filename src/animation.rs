@@ -27,7 +27,7 @@ pub(crate) enum PingPong {
 /// - The animation indices for each of forwards, backwards, left and right
 /// - The animation timer
 /// - A flag that determines whether the animation is played from start to
-/// end or from end to start
+///   end or from end to start
 #[derive(Bundle, Clone)]
 pub(crate) struct Animated {
     pub(crate) sprite_sheet_bundle: SpriteSheetBundle,
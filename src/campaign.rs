@@ -0,0 +1,150 @@
+use bevy::{
+    app::{Plugin, Startup},
+    ecs::system::{Res, ResMut, Resource},
+    prelude::OnEnter,
+};
+use bevy_ecs_ldtk::LevelSelection;
+
+use crate::{
+    level_config::{LevelConfig, LevelConfigHandle, LevelConfigHandles},
+    persistence::{PersistAppExt, Progress},
+    GameState,
+};
+
+/// Number of levels in the campaign. Each level must exist in
+/// `levels/ldtk/Naughty_n_Nice.ldtk` and have its rules in a matching
+/// `levels/Level_<index>.level.ron` file.
+pub(crate) const LEVEL_COUNT: usize = 3;
+
+/// Progress through the campaign of LDtk levels.
+#[derive(Resource)]
+pub(crate) struct Campaign {
+    current_level: usize,
+    unlocked_levels: usize,
+    /// Health left at the end of the previous level, if the player got to
+    /// the current level by winning the previous one.
+    carried_health: Option<u8>,
+    /// Nice presents collected in the levels won so far.
+    presents_collected: u8,
+}
+
+impl Default for Campaign {
+    fn default() -> Self {
+        Self {
+            current_level: 0,
+            unlocked_levels: 1,
+            carried_health: None,
+            presents_collected: 0,
+        }
+    }
+}
+
+impl Campaign {
+    /// Returns the index of the level being played.
+    pub(crate) fn current_level(&self) -> usize {
+        self.current_level
+    }
+
+    /// Returns `true` if the given level has been reached in the campaign and
+    /// can be picked from the level select screen.
+    pub(crate) fn is_unlocked(&self, level: usize) -> bool {
+        level < self.unlocked_levels
+    }
+
+    /// Returns the number of levels that can be picked from the level select
+    /// screen.
+    pub(crate) fn unlocked_levels(&self) -> usize {
+        self.unlocked_levels
+    }
+
+    /// Unlock the given number of levels, as recorded in the [`Progress`] of
+    /// a previous run.
+    pub(crate) fn unlock_levels(&mut self, levels: usize) {
+        self.unlocked_levels = self.unlocked_levels.max(levels.min(LEVEL_COUNT));
+    }
+
+    /// Returns the number of nice presents collected in the levels won so far.
+    pub(crate) fn presents_collected(&self) -> u8 {
+        self.presents_collected
+    }
+
+    /// Start the campaign from the given level, without any carried over stats.
+    pub(crate) fn start_at(&mut self, level: usize) {
+        self.current_level = level.min(LEVEL_COUNT - 1);
+        self.carried_health = None;
+        self.presents_collected = 0;
+    }
+
+    /// Returns the health the player starts the current level with: the
+    /// health left at the end of the previous level, or the starting health
+    /// from the [`LevelConfig`].
+    pub(crate) fn starting_health(&self, level_config: &LevelConfig) -> u8 {
+        self.carried_health.unwrap_or(level_config.starting_health)
+    }
+
+    /// Record the current level as won and move on to the next one, carrying
+    /// over the remaining health. Returns `true` if this was the last level of
+    /// the campaign.
+    pub(crate) fn complete_level(&mut self, health: u8, presents: u8) -> bool {
+        self.presents_collected = self.presents_collected.saturating_add(presents);
+
+        if self.current_level + 1 >= LEVEL_COUNT {
+            return true;
+        }
+
+        self.current_level += 1;
+        self.unlocked_levels = self.unlocked_levels.max(self.current_level + 1);
+        self.carried_health = Some(health);
+        false
+    }
+}
+
+/// Plugin tracking progress through the campaign and selecting the LDtk
+/// level and rules of the current level when a round starts. The levels
+/// unlocked and the levels won are saved in the [`Progress`].
+pub(crate) struct CampaignPlugin;
+
+impl Plugin for CampaignPlugin {
+    fn build(&self, app: &mut bevy::prelude::App) {
+        app.init_resource::<Campaign>()
+            .init_persisted_resource::<Progress>()
+            .add_systems(Startup, restore_progress)
+            .add_systems(OnEnter(GameState::Game), select_campaign_level)
+            .add_systems(OnEnter(GameState::LevelComplete), record_level_won)
+            .add_systems(
+                OnEnter(GameState::Win),
+                (record_level_won, record_campaign_won),
+            );
+    }
+}
+
+/// Unlock the levels reached in previous runs.
+fn restore_progress(progress: Res<Progress>, mut campaign: ResMut<Campaign>) {
+    campaign.unlock_levels(progress.unlocked_levels);
+}
+
+/// Record the level just won, and the levels it unlocked.
+fn record_level_won(campaign: Res<Campaign>, mut progress: ResMut<Progress>) {
+    progress.levels_won = progress.levels_won.saturating_add(1);
+    progress.unlocked_levels = progress.unlocked_levels.max(campaign.unlocked_levels());
+}
+
+/// Record the campaign just won, and the presents collected over it.
+fn record_campaign_won(campaign: Res<Campaign>, mut progress: ResMut<Progress>) {
+    progress.campaigns_won = progress.campaigns_won.saturating_add(1);
+    progress.best_campaign_presents = progress
+        .best_campaign_presents
+        .max(campaign.presents_collected());
+}
+
+/// Point the [`LevelSelection`] and [`LevelConfigHandle`] at the current level
+/// of the [`Campaign`].
+pub(crate) fn select_campaign_level(
+    campaign: Res<Campaign>,
+    mut level_selection: ResMut<LevelSelection>,
+    mut level_config: ResMut<LevelConfigHandle>,
+    level_config_handles: Res<LevelConfigHandles>,
+) {
+    *level_selection = LevelSelection::Index(campaign.current_level());
+    level_config.handle = level_config_handles.handle(campaign.current_level());
+}
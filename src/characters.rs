@@ -1,16 +1,23 @@
+use bevy::math::Vec2;
+
 mod basic_character;
 mod character_with_status;
+mod effects;
 mod inventory;
+mod player;
+mod sprite_sheet;
 mod status;
 
 pub(crate) use basic_character::BasicCharacter;
 pub(crate) use character_with_status::CharacterWithStatus;
+pub(crate) use effects::{ActiveEffects, Effect, SCORE_MULTIPLIER, SPEED_BOOST_FACTOR};
 pub(crate) use inventory::Inventory;
+pub(crate) use player::Player;
+pub(crate) use sprite_sheet::{load_sprite_sheets, SpriteSheet, SpriteSheetAtlases};
 pub(crate) use status::Status;
 
-/// Used to store current movement direction of a sprite.
-/// Also used to determine which animation frames are used
-/// to draw the sprite.
+/// The direction a sprite is facing, derived from its movement vector.
+/// Used to determine which animation frames are used to draw the sprite.
 #[derive(Clone, Copy)]
 pub(crate) enum Direction {
     Static,
@@ -20,6 +27,26 @@ pub(crate) enum Direction {
     Right,
 }
 
+impl Direction {
+    /// Returns the facing for the given movement vector, along its dominant
+    /// axis. Diagonal movement with equal axes faces left or right.
+    pub(crate) fn from_vector(vector: Vec2) -> Self {
+        if vector == Vec2::ZERO {
+            Direction::Static
+        } else if vector.x.abs() >= vector.y.abs() {
+            if vector.x < 0. {
+                Direction::Left
+            } else {
+                Direction::Right
+            }
+        } else if vector.y < 0. {
+            Direction::Back
+        } else {
+            Direction::Forward
+        }
+    }
+}
+
 /// A character can be in one of the following state:
 /// - `Alive` and able to move
 /// - `Celebrating` completed the level
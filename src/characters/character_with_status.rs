@@ -1,11 +1,13 @@
 use bevy::ecs::bundle::Bundle;
 
-use super::{status::Status, BasicCharacter, Inventory};
+use super::{status::Status, ActiveEffects, BasicCharacter, Inventory};
 
-/// This [`Bundle`] adds a [`Status`] and an [`Inventory`]to a [`BasicCharacter`].
+/// This [`Bundle`] adds a [`Status`], an [`Inventory`] and the
+/// [`ActiveEffects`] of presents to a [`BasicCharacter`].
 #[derive(Bundle)]
 pub(crate) struct CharacterWithStatus {
     pub(crate) basic_character: BasicCharacter,
     pub(crate) status: Status,
     pub(crate) inventory: Inventory,
+    pub(crate) active_effects: ActiveEffects,
 }
@@ -0,0 +1,154 @@
+use bevy::{
+    ecs::component::Component,
+    time::{Timer, TimerMode},
+    utils::Duration,
+};
+
+/// How much faster a player walks during a [`Effect::SpeedBoost`].
+pub(crate) const SPEED_BOOST_FACTOR: f32 = 1.5;
+
+/// How many times their value nice presents are worth during a
+/// [`Effect::ScoreMultiplier`].
+pub(crate) const SCORE_MULTIPLIER: u8 = 2;
+
+/// Timed effects a player gets from collecting presents.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub(crate) enum Effect {
+    /// Walking [`SPEED_BOOST_FACTOR`] times faster.
+    SpeedBoost,
+    /// The next hit from a naughty present or an enemy is absorbed, ending
+    /// the effect.
+    Shield,
+    /// Nice presents are worth [`SCORE_MULTIPLIER`] times their value.
+    ScoreMultiplier,
+}
+
+impl Effect {
+    /// How long the effect lasts, in seconds.
+    fn seconds(&self) -> f32 {
+        match self {
+            Effect::SpeedBoost => 8.,
+            Effect::Shield => 15.,
+            Effect::ScoreMultiplier => 10.,
+        }
+    }
+
+    /// Name of the effect shown on the scoreboard.
+    pub(crate) fn label(&self) -> &'static str {
+        match self {
+            Effect::SpeedBoost => "Speed",
+            Effect::Shield => "Shield",
+            Effect::ScoreMultiplier => "x2",
+        }
+    }
+}
+
+/// Component tracking the [`Effect`]s active on a player, with the time left
+/// for each.
+#[derive(Component, Default)]
+pub(crate) struct ActiveEffects {
+    effects: Vec<(Effect, Timer)>,
+}
+
+impl ActiveEffects {
+    /// Start the effect, or start its time over if it is already active.
+    /// Returns `true` if the effect wasn't active.
+    pub(crate) fn start(&mut self, effect: Effect) -> bool {
+        let timer = Timer::from_seconds(effect.seconds(), TimerMode::Once);
+
+        match self
+            .effects
+            .iter_mut()
+            .find(|(active, _)| *active == effect)
+        {
+            Some((_, active_timer)) => {
+                *active_timer = timer;
+                false
+            }
+            None => {
+                self.effects.push((effect, timer));
+                true
+            }
+        }
+    }
+
+    /// Returns `true` while the effect is active.
+    pub(crate) fn is_active(&self, effect: Effect) -> bool {
+        self.effects.iter().any(|(active, _)| *active == effect)
+    }
+
+    /// End the effect before its time runs out, e.g. once a shield has
+    /// absorbed a hit. Returns `true` if the effect was active.
+    pub(crate) fn end(&mut self, effect: Effect) -> bool {
+        let count = self.effects.len();
+        self.effects.retain(|(active, _)| *active != effect);
+        self.effects.len() != count
+    }
+
+    /// Advance the time of the active effects, ending those whose time ran
+    /// out. Returns the effects that ended.
+    pub(crate) fn tick(&mut self, delta: Duration) -> Vec<Effect> {
+        let mut ended = Vec::new();
+        self.effects.retain_mut(|(effect, timer)| {
+            if timer.tick(delta).finished() {
+                ended.push(*effect);
+                false
+            } else {
+                true
+            }
+        });
+        ended
+    }
+
+    /// Returns the active effects, in the order they started, with the time
+    /// left for each in seconds.
+    pub(crate) fn iter(&self) -> impl Iterator<Item = (Effect, f32)> + '_ {
+        self.effects
+            .iter()
+            .map(|(effect, timer)| (*effect, timer.remaining_secs()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn start_restarts_active_effect() {
+        let mut active_effects = ActiveEffects::default();
+
+        assert!(active_effects.start(Effect::Shield));
+        active_effects.tick(Duration::from_secs(10));
+        assert!(!active_effects.start(Effect::Shield));
+
+        let remaining = active_effects.iter().collect::<Vec<_>>();
+        assert_eq!(remaining, [(Effect::Shield, Effect::Shield.seconds())]);
+    }
+
+    #[test]
+    fn end_effect() {
+        let mut active_effects = ActiveEffects::default();
+        active_effects.start(Effect::Shield);
+        active_effects.start(Effect::SpeedBoost);
+
+        assert!(active_effects.end(Effect::Shield));
+        assert!(!active_effects.end(Effect::Shield));
+        assert!(!active_effects.is_active(Effect::Shield));
+        assert!(active_effects.is_active(Effect::SpeedBoost));
+    }
+
+    #[test]
+    fn tick_ends_effects_out_of_time() {
+        let mut active_effects = ActiveEffects::default();
+        active_effects.start(Effect::SpeedBoost);
+        active_effects.start(Effect::ScoreMultiplier);
+
+        assert!(active_effects.tick(Duration::from_secs(5)).is_empty());
+        assert_eq!(
+            active_effects.tick(Duration::from_secs(4)),
+            [Effect::SpeedBoost]
+        );
+        assert!(!active_effects.is_active(Effect::SpeedBoost));
+        assert!(active_effects.is_active(Effect::ScoreMultiplier));
+    }
+}
@@ -4,6 +4,9 @@ use bevy::ecs::component::Component;
 #[derive(Component)]
 pub(crate) struct Inventory {
     number_of_presents: u8,
+    /// Points value of the presents, counting those collected during a score
+    /// multiplier several times.
+    value_of_presents: u32,
 }
 
 impl Inventory {
@@ -11,17 +14,25 @@ impl Inventory {
     pub(crate) fn new() -> Self {
         Self {
             number_of_presents: 0,
+            value_of_presents: 0,
         }
     }
 
-    /// Add a present to this [`Inventory`].
-    pub(crate) fn add_present(&mut self) {
+    /// Add a present worth the given number of presents' points to this
+    /// [`Inventory`].
+    pub(crate) fn add_present(&mut self, value: u8) {
         self.number_of_presents += 1;
-        println!("Presents: {}", self.number_of_presents);
+        self.value_of_presents += u32::from(value);
     }
 
     /// Returns the number of presents in this [`Inventory`].
     pub(crate) fn number_of_presents(&self) -> u8 {
         self.number_of_presents
     }
+
+    /// Returns the points value of the presents in this [`Inventory`], in
+    /// number of presents.
+    pub(crate) fn value_of_presents(&self) -> u32 {
+        self.value_of_presents
+    }
 }
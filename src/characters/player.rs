@@ -0,0 +1,22 @@
+use bevy::ecs::component::Component;
+
+/// Component marking a character controlled by a player, as opposed to the
+/// enemies. Each player has their own [`super::Status`] and
+/// [`super::Inventory`].
+#[derive(Component, Clone, Copy)]
+pub(crate) struct Player {
+    index: usize,
+}
+
+impl Player {
+    /// Creates a new [`Player`] with the given index, the first player being
+    /// 0.
+    pub(crate) fn new(index: usize) -> Self {
+        Self { index }
+    }
+
+    /// Returns the index of this [`Player`], the first player being 0.
+    pub(crate) fn index(&self) -> usize {
+        self.index
+    }
+}
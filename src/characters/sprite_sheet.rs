@@ -0,0 +1,131 @@
+use bevy::{
+    asset::{AssetServer, Assets, Handle},
+    ecs::system::{Commands, Res, ResMut, Resource},
+    math::{Vec2, Vec3},
+    sprite::{SpriteSheetBundle, TextureAtlas, TextureAtlasSprite},
+    time::{Timer, TimerMode},
+    transform::components::Transform,
+};
+
+use crate::animation::{Animated, AnimationIndices, AnimationTimer, PingPong};
+
+/// Time each animation frame is displayed for, in seconds.
+const FRAME_SECONDS: f32 = 0.3;
+
+/// The character sprite sheets shipped with the game. Each row holds three
+/// 48x48 frames of animation: facing the camera, then walking left, right and
+/// away from the camera.
+#[derive(Clone, Copy)]
+pub(crate) enum SpriteSheet {
+    /// The deer, with two more rows for the celebration and death animations.
+    Deer,
+    /// The flamingo, without celebration nor death animations.
+    Flamingo,
+}
+
+impl SpriteSheet {
+    fn path(&self) -> &'static str {
+        match self {
+            SpriteSheet::Deer => "sprites/deer.png",
+            SpriteSheet::Flamingo => "sprites/flamingo.png",
+        }
+    }
+
+    fn rows(&self) -> usize {
+        match self {
+            SpriteSheet::Deer => 6,
+            SpriteSheet::Flamingo => 4,
+        }
+    }
+
+    /// Load the sprite sheet and create its texture atlas.
+    fn texture_atlas(&self, asset_server: &AssetServer) -> TextureAtlas {
+        TextureAtlas::from_grid(
+            asset_server.load(self.path()),
+            Vec2::new(48.0, 48.0),
+            3,
+            self.rows(),
+            None,
+            None,
+        )
+    }
+
+    /// Returns the start and end frames of each animation. The flamingo shows
+    /// itself standing in place of the animations it doesn't have.
+    fn animation_indices(&self) -> AnimationIndices {
+        let (celebrate, die) = match self {
+            SpriteSheet::Deer => ((12, 14), (15, 17)),
+            SpriteSheet::Flamingo => ((1, 1), (1, 1)),
+        };
+
+        AnimationIndices {
+            forward_start: 9,
+            forward_end: 11,
+            back_start: 0,
+            back_end: 2,
+            left_start: 3,
+            left_end: 5,
+            right_start: 6,
+            right_end: 8,
+            celebrate_start: celebrate.0,
+            celebrate_end: celebrate.1,
+            die_start: die.0,
+            die_end: die.1,
+        }
+    }
+
+    /// Returns the [`Animated`] bundle of a character drawn with the given
+    /// texture atlas of this sprite sheet, starting facing the camera.
+    pub(crate) fn animated(
+        &self,
+        texture_atlas: Handle<TextureAtlas>,
+        translation: Vec3,
+    ) -> Animated {
+        let animation_indices = self.animation_indices();
+
+        Animated {
+            sprite_sheet_bundle: SpriteSheetBundle {
+                texture_atlas,
+                sprite: TextureAtlasSprite::new(animation_indices.back_start + 1),
+                transform: Transform::from_translation(translation),
+                ..Default::default()
+            },
+            animation_indices,
+            animation_timer: AnimationTimer(Timer::from_seconds(
+                FRAME_SECONDS,
+                TimerMode::Repeating,
+            )),
+            ping_pong: PingPong::Ping,
+        }
+    }
+}
+
+/// The texture atlas of each [`SpriteSheet`], shared by all the characters
+/// drawn with it.
+#[derive(Resource)]
+pub(crate) struct SpriteSheetAtlases {
+    deer: Handle<TextureAtlas>,
+    flamingo: Handle<TextureAtlas>,
+}
+
+impl SpriteSheetAtlases {
+    /// Returns the texture atlas of the sprite sheet.
+    pub(crate) fn get(&self, sprite_sheet: SpriteSheet) -> Handle<TextureAtlas> {
+        match sprite_sheet {
+            SpriteSheet::Deer => self.deer.clone(),
+            SpriteSheet::Flamingo => self.flamingo.clone(),
+        }
+    }
+}
+
+/// Load the [`SpriteSheetAtlases`] at startup.
+pub(crate) fn load_sprite_sheets(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mut texture_atlases: ResMut<Assets<TextureAtlas>>,
+) {
+    commands.insert_resource(SpriteSheetAtlases {
+        deer: texture_atlases.add(SpriteSheet::Deer.texture_atlas(&asset_server)),
+        flamingo: texture_atlases.add(SpriteSheet::Flamingo.texture_atlas(&asset_server)),
+    });
+}
@@ -7,19 +7,30 @@ use super::CharacterState;
 pub(crate) struct Status {
     state: CharacterState,
     pub(crate) health: u8,
+    /// Health can't be restored above this.
+    max_health: u8,
     pub(crate) game_over: bool,
 }
 
 impl Status {
-    /// Creates a new [`Status`] with the given health.
+    /// Creates a new [`Status`] with the given health, which is also the most
+    /// it can be restored to.
     pub(crate) fn new(health: u8) -> Self {
         Self {
             state: CharacterState::Alive,
             health,
+            max_health: health,
             game_over: false,
         }
     }
 
+    /// Allow health to be restored up to `max_health`, if more than the
+    /// current health.
+    pub(crate) fn with_max_health(mut self, max_health: u8) -> Self {
+        self.max_health = max_health.max(self.health);
+        self
+    }
+
     /// Returns the current [`Status`] of the character.
     pub(crate) fn state(&self) -> CharacterState {
         self.state
@@ -31,27 +42,40 @@ impl Status {
         // we want to remove more health than we have, we're
         // dead.
         if self.health < to_remove {
+            self.health = 0;
             self.state = CharacterState::Dead;
             return;
         }
 
         self.health -= to_remove;
-        println!("Health: {}", self.health);
 
         if self.health == 0 {
             self.state = CharacterState::Dead;
         }
     }
 
-    /// Indicate that the character is celebrating.
-    pub(crate) fn celebrate(&mut self) {
-        self.state = CharacterState::Celebrating;
+    /// Restore the given number of health points, up to the maximum health.
+    /// Dead characters stay dead.
+    pub(crate) fn heal(&mut self, to_restore: u8) {
+        if self.state == CharacterState::Dead {
+            return;
+        }
+
+        self.health = self.health.saturating_add(to_restore).min(self.max_health);
     }
 
-    /// End the character celebration.
-    pub(crate) fn end_celebration(&mut self) {
-        if self.state == CharacterState::Celebrating {
-            self.state = CharacterState::Alive;
+    /// Kill the character whatever health it has left, e.g. when the time
+    /// runs out.
+    pub(crate) fn die(&mut self) {
+        self.state = CharacterState::Dead;
+    }
+
+    /// Indicate that the character is celebrating. Dead characters stay dead.
+    pub(crate) fn celebrate(&mut self) {
+        if self.state == CharacterState::Dead {
+            return;
         }
+
+        self.state = CharacterState::Celebrating;
     }
 }
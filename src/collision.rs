@@ -1,77 +1,298 @@
 use bevy::{
-    app::{Plugin, Update},
+    app::{Plugin, PostUpdate},
+    asset::Assets,
     ecs::{
         entity::Entity,
+        event::EventWriter,
         query::With,
-        system::{Commands, Query},
+        schedule::{common_conditions::in_state, Condition, IntoSystemConfigs},
+        system::{Commands, Query, Res},
     },
+    hierarchy::DespawnRecursiveExt,
     math::Vec2,
     sprite::collide_aabb::collide,
-    transform::components::Transform,
+    transform::{components::GlobalTransform, TransformSystem},
 };
 
 use crate::{
-    characters::{Inventory, Status},
-    markers::CharacterMarker,
+    characters::{
+        ActiveEffects, CharacterState, Effect, Inventory, Player, Status, SCORE_MULTIPLIER,
+        SPEED_BOOST_FACTOR,
+    },
+    enemy::ContactDamage,
+    events::{EffectStarted, LevelWon, PlayerDamaged, PlayerDied, PlayerHealed, PresentCollected},
+    level_config::{LevelConfig, LevelConfigHandle},
+    markers::EnemyMarker,
+    moveable::Moveable,
+    pause::PauseState,
+    play_mode::PlayMode,
     present::{Present, PresentType},
+    GameState,
 };
 
-/// Check player character for collisions with other entities
+/// Size of the box tested for contact between the player and an enemy,
+/// smaller than their sprites so that brushing past doesn't hurt.
+const CONTACT_SIZE: Vec2 = Vec2::new(32., 32.);
+
+/// Check the player characters for collisions with other entities. Collisions
+/// are tested once transforms have been propagated, so that presents placed
+/// in the LDtk level are tested at their position in the world.
 pub(crate) struct CollisionHandler;
 
 impl Plugin for CollisionHandler {
     fn build(&self, app: &mut bevy::prelude::App) {
-        app.add_systems(Update, hit_test_presents);
+        app.add_systems(
+            PostUpdate,
+            (hit_test_presents, hit_test_enemies)
+                .after(TransformSystem::TransformPropagate)
+                .run_if(in_state(GameState::Game).and_then(in_state(PauseState::Running))),
+        );
     }
 }
 
-/// Check for collisions with presents and update player state depending on the
-/// type of present.
+/// Check for collisions between the players and the presents and update the
+/// state of the player that touched the present depending on its type. Only
+/// players still `Alive` pick presents up. Sends the matching gameplay events
+/// so other systems can react to the change. Naughty presents hurt whoever
+/// grabs them unless shielded, the nice and golden ones count towards the win
+/// target of the player who collected them, or of both players together in a
+/// [`PlayMode::Cooperative`] game. The other presents heal the player or start
+/// an [`Effect`] on them. In a [`PlayMode::Versus`] game, the player with the
+/// most presents wins once the nice presents have run out.
+#[allow(clippy::too_many_arguments)]
 fn hit_test_presents(
     mut commands: Commands,
-    mut player_query: Query<(&Transform, &mut Status, &mut Inventory), With<CharacterMarker>>,
-    mut present_query: Query<(Entity, &Transform, &Present)>,
+    mut player_query: Query<
+        (
+            Entity,
+            &GlobalTransform,
+            &mut Status,
+            &mut Inventory,
+            &mut ActiveEffects,
+            &mut Moveable,
+        ),
+        With<Player>,
+    >,
+    present_query: Query<(Entity, &GlobalTransform, &Present)>,
+    mut present_collected_events: EventWriter<PresentCollected>,
+    mut player_damaged_events: EventWriter<PlayerDamaged>,
+    mut player_healed_events: EventWriter<PlayerHealed>,
+    mut effect_started_events: EventWriter<EffectStarted>,
+    mut player_died_events: EventWriter<PlayerDied>,
+    mut level_won_events: EventWriter<LevelWon>,
+    level_config: Res<LevelConfigHandle>,
+    level_configs: Res<Assets<LevelConfig>>,
+    play_mode: Res<PlayMode>,
 ) {
-    // Both the presents and the player are the same size
+    let win_target = level_config.get(&level_configs).win_target;
+    let cooperative = *play_mode == PlayMode::Cooperative;
+
+    // Both the presents and the players are the same size
     let entity_size = Vec2::new(48., 48.);
 
-    // Get the single player entity
-    match player_query.get_single_mut() {
-        Ok((player_transform, mut status, mut inventory)) => {
-            // Loop through the presents and test each one against the player
-            for (entity, present_transform, present) in &mut present_query {
-                if collide(
-                    player_transform.translation,
-                    entity_size,
-                    present_transform.translation,
-                    entity_size,
-                )
-                .is_some()
-                {
-                    match present.present_type() {
-                        PresentType::Naughty(health_to_remove) => {
-                            status.remove_health(health_to_remove)
-                        }
-                        PresentType::Nice => {
-                            // Add present to inventory and check for win condition
-                            inventory.add_present();
-                            check_and_update_win_condition(&inventory, &mut status)
-                        }
-                    }
-
-                    // Despawn the present, we're done with it
-                    commands.entity(entity).despawn();
+    // LevelWon is sent once per round, when the first player wins
+    let already_won = player_query
+        .iter()
+        .any(|(_, _, status, _, _, _)| has_won(status));
+    let mut team_presents = player_query
+        .iter()
+        .map(|(_, _, _, inventory, _, _)| inventory.number_of_presents())
+        .fold(0, u8::saturating_add);
+    let mut collected = false;
+    let mut nice_presents_left = present_query
+        .iter()
+        .filter(|(_, _, present)| {
+            matches!(
+                present.present_type(),
+                PresentType::Nice | PresentType::Golden
+            )
+        })
+        .count();
+
+    // Loop through the presents and test each one against the players still
+    // playing, the first player found touching a present gets it
+    for (entity, present_transform, present) in &present_query {
+        let Some((player, _, mut status, mut inventory, mut active_effects, mut moveable)) =
+            player_query
+                .iter_mut()
+                .filter(|(_, _, status, _, _, _)| status.state() == CharacterState::Alive)
+                .find(|(_, player_transform, _, _, _, _)| {
+                    collide(
+                        player_transform.translation(),
+                        entity_size,
+                        present_transform.translation(),
+                        entity_size,
+                    )
+                    .is_some()
+                })
+        else {
+            continue;
+        };
+
+        let mut start_effect = |effect| {
+            if active_effects.start(effect) && effect == Effect::SpeedBoost {
+                moveable.speed.0 *= SPEED_BOOST_FACTOR;
+            }
+            effect_started_events.send(EffectStarted { effect });
+        };
+
+        match present.present_type() {
+            PresentType::Naughty(health_to_remove) => {
+                // The shield absorbs the hit, and is used up doing so
+                if active_effects.end(Effect::Shield) {
+                    commands.entity(entity).despawn_recursive();
+                    continue;
+                }
+
+                status.remove_health(health_to_remove);
+                player_damaged_events.send(PlayerDamaged {
+                    character: player,
+                    health: status.health,
+                });
+
+                if status.state() == CharacterState::Dead {
+                    player_died_events.send(PlayerDied);
+                }
+            }
+            PresentType::Nice | PresentType::Golden => {
+                if present.present_type() == PresentType::Golden {
+                    start_effect(Effect::ScoreMultiplier);
+                }
+
+                // Add present to inventory and check for win condition
+                let value = if active_effects.is_active(Effect::ScoreMultiplier) {
+                    SCORE_MULTIPLIER
+                } else {
+                    1
+                };
+                inventory.add_present(value);
+                team_presents = team_presents.saturating_add(1);
+                nice_presents_left -= 1;
+                collected = true;
+                present_collected_events.send(PresentCollected {
+                    character: player,
+                    total: inventory.number_of_presents(),
+                });
+
+                if !cooperative {
+                    check_and_update_win_condition(
+                        inventory.number_of_presents(),
+                        &mut status,
+                        win_target,
+                    );
+                }
+            }
+            PresentType::Healing(health_to_restore) => {
+                status.heal(health_to_restore);
+                player_healed_events.send(PlayerHealed {
+                    character: player,
+                    health: status.health,
+                });
+            }
+            PresentType::SpeedBoost => start_effect(Effect::SpeedBoost),
+            PresentType::Shield => start_effect(Effect::Shield),
+        }
+
+        // Despawn the present, we're done with it. Presents placed in the
+        // LDtk level are children of the level.
+        commands.entity(entity).despawn_recursive();
+    }
+
+    // The players still standing celebrate together once their presents add
+    // up to the win target
+    if cooperative && collected {
+        for (_, _, mut status, _, _, _) in &mut player_query {
+            if status.state() == CharacterState::Alive {
+                check_and_update_win_condition(team_presents, &mut status, win_target);
+            }
+        }
+    }
+
+    // When the nice presents run out before anyone reaches the win target,
+    // the player still standing with the most presents wins rather than the
+    // round going on forever
+    let won = player_query
+        .iter()
+        .any(|(_, _, status, _, _, _)| has_won(status));
+    if *play_mode == PlayMode::Versus && collected && nice_presents_left == 0 && !won {
+        if let Some((_, _, mut status, _, _, _)) = player_query
+            .iter_mut()
+            .filter(|(_, _, status, _, _, _)| status.state() == CharacterState::Alive)
+            .max_by_key(|(_, _, _, inventory, _, _)| inventory.number_of_presents())
+        {
+            status.celebrate();
+        }
+    }
+
+    let won = player_query
+        .iter()
+        .any(|(_, _, status, _, _, _)| has_won(status));
+    if won && !already_won {
+        level_won_events.send(LevelWon);
+    }
+}
+
+/// Returns `true` if the player is celebrating, or has finished celebrating.
+fn has_won(status: &Status) -> bool {
+    status.state() == CharacterState::Celebrating
+        || (status.game_over && status.state() != CharacterState::Dead)
+}
+
+/// Check for contact between the players and the enemies, removing the
+/// enemy's [`ContactDamage`] from the health of the player touched. Only
+/// players still `Alive` are hurt, and a shield absorbs the hit like it does
+/// for naughty presents.
+fn hit_test_enemies(
+    mut player_query: Query<
+        (Entity, &GlobalTransform, &mut Status, &mut ActiveEffects),
+        With<Player>,
+    >,
+    mut enemy_query: Query<(&GlobalTransform, &mut ContactDamage), With<EnemyMarker>>,
+    mut player_damaged_events: EventWriter<PlayerDamaged>,
+    mut player_died_events: EventWriter<PlayerDied>,
+) {
+    for (player, player_transform, mut status, mut active_effects) in &mut player_query {
+        for (enemy_transform, mut contact_damage) in &mut enemy_query {
+            if status.state() != CharacterState::Alive {
+                break;
+            }
+
+            if collide(
+                player_transform.translation(),
+                CONTACT_SIZE,
+                enemy_transform.translation(),
+                CONTACT_SIZE,
+            )
+            .is_none()
+            {
+                continue;
+            }
+
+            if let Some(damage) = contact_damage.hit() {
+                // The shield is used up, the enemy still has to wait before
+                // it can hurt the player again
+                if active_effects.end(Effect::Shield) {
+                    continue;
+                }
+
+                status.remove_health(damage);
+                player_damaged_events.send(PlayerDamaged {
+                    character: player,
+                    health: status.health,
+                });
+
+                if status.state() == CharacterState::Dead {
+                    player_died_events.send(PlayerDied);
                 }
             }
         }
-        Err(_) => println!("Either player is missing or there is more than one player"),
     }
 }
 
-/// Compare the number of presents in the inventory with the win condition and
-/// set the player celebrating if the win condition is met.
-fn check_and_update_win_condition(inventory: &Inventory, status: &mut Status) {
-    if inventory.number_of_presents() == 5 {
+/// Compare the number of presents collected with the win target of the level
+/// and set the player celebrating if the win condition is met.
+fn check_and_update_win_condition(presents: u8, status: &mut Status, win_target: u8) {
+    if presents >= win_target {
         status.celebrate();
     }
 }
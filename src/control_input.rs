@@ -1,16 +1,69 @@
-use bevy::app::{Plugin, Update};
+use bevy::{
+    app::{Plugin, Update},
+    ecs::schedule::{
+        common_conditions::{in_state, not},
+        Condition, IntoSystemConfigs,
+    },
+    math::Vec2,
+};
 
 mod audio_keyboard;
 mod character_keyboard;
+mod gamepad;
+mod input_map;
 
 use audio_keyboard::mute;
 use character_keyboard::handle_keyboard_for_character;
+use gamepad::{handle_gamepad_connections, handle_gamepad_for_character, mute_with_gamepad};
 
-/// This plugin handles keyboard input.
+pub(crate) use gamepad::ActiveGamepad;
+pub(crate) use input_map::{InputAction, InputMap};
+
+use crate::{
+    pause::PauseState, persistence::PersistAppExt, score::typing_high_score_name, GameState,
+};
+
+/// Returns the movement direction for the up, down, left and right actions
+/// pressed, combining them so that two actions move diagonally.
+fn movement_direction(pressed: impl Fn(InputAction) -> bool) -> Vec2 {
+    let mut direction = Vec2::ZERO;
+    if pressed(InputAction::MoveUp) {
+        direction.y += 1.;
+    }
+    if pressed(InputAction::MoveLeft) {
+        direction.x -= 1.;
+    }
+    if pressed(InputAction::MoveDown) {
+        direction.y -= 1.;
+    }
+    if pressed(InputAction::MoveRight) {
+        direction.x += 1.;
+    }
+    direction
+}
+
+/// This plugin handles keyboard and gamepad input, through the bindings of
+/// the [`InputMap`] loaded from the user's config directory.
 pub(crate) struct ControlInput;
 
 impl Plugin for ControlInput {
     fn build(&self, app: &mut bevy::prelude::App) {
-        app.add_systems(Update, (handle_keyboard_for_character, mute));
+        app.init_resource::<ActiveGamepad>()
+            .init_persisted_resource::<InputMap>()
+            .add_systems(
+                Update,
+                (handle_keyboard_for_character, handle_gamepad_for_character)
+                    .chain()
+                    .run_if(in_state(GameState::Game).and_then(in_state(PauseState::Running))),
+            )
+            .add_systems(
+                Update,
+                (
+                    handle_gamepad_connections,
+                    // Keep the mute key for typing a high score name
+                    mute.run_if(not(typing_high_score_name)),
+                    mute_with_gamepad,
+                ),
+            );
     }
 }
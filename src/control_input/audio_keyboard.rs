@@ -1,22 +1,21 @@
-use crate::game_audio::Audio;
-use bevy::prelude::AudioSinkPlayback;
 use bevy::{
-    audio::AudioSink,
-    ecs::{
-        query::With,
-        system::{Query, Res},
-    },
+    ecs::system::{Res, ResMut},
     input::{keyboard::KeyCode, Input},
 };
 
-/// Handle toggling the background music on and off.
+use crate::settings::Settings;
+
+use super::{InputAction, InputMap};
+
+/// Handle muting and unmuting the music and sound effects with the key bound
+/// to [`InputAction::Mute`]. The mute is kept in the [`Settings`] so that it is
+/// remembered across runs.
 pub(crate) fn mute(
     keyboard_input: Res<Input<KeyCode>>,
-    music_controller: Query<&AudioSink, With<Audio>>,
+    input_map: Res<InputMap>,
+    mut settings: ResMut<Settings>,
 ) {
-    if keyboard_input.just_pressed(KeyCode::M) {
-        if let Ok(sink) = music_controller.get_single() {
-            sink.toggle();
-        }
+    if input_map.just_pressed(InputAction::Mute, &keyboard_input) {
+        settings.muted = !settings.muted;
     }
 }
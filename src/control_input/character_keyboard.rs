@@ -1,32 +1,40 @@
 use bevy::{
-    ecs::{
-        query::With,
-        system::{Query, Res},
-    },
+    ecs::system::{Query, Res},
     input::{keyboard::KeyCode, Input},
 };
 
-use crate::{characters::Direction, markers::CharacterMarker, moveable::Moveable};
+use crate::{characters::Player, moveable::Moveable, play_mode::PlayMode};
 
-/// Handle control of the player sprite. Updates the [`Moveable`] component as
-/// required.
+use super::{movement_direction, InputAction, InputMap};
+
+/// Arrow keys moving the second player in two player games. The first player
+/// then only moves with the first key bound to each action, the one that can
+/// be rebound.
+const SECOND_PLAYER_KEYS: [(InputAction, KeyCode); 4] = [
+    (InputAction::MoveUp, KeyCode::Up),
+    (InputAction::MoveDown, KeyCode::Down),
+    (InputAction::MoveLeft, KeyCode::Left),
+    (InputAction::MoveRight, KeyCode::Right),
+];
+
+/// Handle control of the player sprites with the keys bound in the
+/// [`InputMap`], or the [`SECOND_PLAYER_KEYS`] for the second player. Updates
+/// the [`Moveable`] components as required.
 pub(crate) fn handle_keyboard_for_character(
     keyboard_input: Res<Input<KeyCode>>,
-    mut query: Query<&mut Moveable, With<CharacterMarker>>,
+    input_map: Res<InputMap>,
+    play_mode: Res<PlayMode>,
+    mut query: Query<(&mut Moveable, &Player)>,
 ) {
-    query.for_each_mut(|mut moveable| {
-        if keyboard_input.any_just_released([KeyCode::W, KeyCode::A, KeyCode::S, KeyCode::D]) {
-            moveable.direction = Direction::Static;
-        }
-
-        if keyboard_input.pressed(KeyCode::W) {
-            moveable.direction = Direction::Forward;
-        } else if keyboard_input.pressed(KeyCode::A) {
-            moveable.direction = Direction::Left;
-        } else if keyboard_input.pressed(KeyCode::S) {
-            moveable.direction = Direction::Back;
-        } else if keyboard_input.pressed(KeyCode::D) {
-            moveable.direction = Direction::Right;
-        }
-    });
+    for (mut moveable, player) in &mut query {
+        let direction =
+            movement_direction(|action| match (play_mode.player_count(), player.index()) {
+                (1, _) => input_map.pressed(action, &keyboard_input),
+                (_, 0) => input_map.first_key_pressed(action, &keyboard_input),
+                _ => SECOND_PLAYER_KEYS
+                    .iter()
+                    .any(|(bound, key)| *bound == action && keyboard_input.pressed(*key)),
+            });
+        moveable.set_direction(direction);
+    }
 }
@@ -0,0 +1,108 @@
+use bevy::{
+    ecs::{
+        event::EventReader,
+        system::{Query, Res, ResMut, Resource},
+    },
+    input::{
+        gamepad::{
+            Gamepad, GamepadAxis, GamepadAxisType, GamepadButton, GamepadConnectionEvent, Gamepads,
+        },
+        Axis, Input,
+    },
+    math::Vec2,
+};
+
+use crate::{characters::Player, moveable::Moveable, settings::Settings};
+
+use super::{movement_direction, InputAction, InputMap};
+
+/// The gamepad controlling the first player and the menus, if any is
+/// connected.
+#[derive(Resource, Default)]
+pub(crate) struct ActiveGamepad(pub(crate) Option<Gamepad>);
+
+/// Pick up a gamepad when it is plugged in, and switch to another connected
+/// gamepad (if any) when the active one is unplugged.
+pub(crate) fn handle_gamepad_connections(
+    mut connection_events: EventReader<GamepadConnectionEvent>,
+    gamepads: Res<Gamepads>,
+    mut active_gamepad: ResMut<ActiveGamepad>,
+) {
+    for event in connection_events.iter() {
+        if event.connected() {
+            if active_gamepad.0.is_none() {
+                active_gamepad.0 = Some(event.gamepad);
+            }
+        } else if active_gamepad.0 == Some(event.gamepad) {
+            active_gamepad.0 = gamepads.iter().find(|gamepad| *gamepad != event.gamepad);
+        }
+    }
+}
+
+/// Returns the gamepad controlling the given player: the active gamepad for
+/// the first player, and the other connected gamepads in turn for the next
+/// players.
+fn player_gamepad(
+    player: &Player,
+    active_gamepad: &ActiveGamepad,
+    gamepads: &Gamepads,
+) -> Option<Gamepad> {
+    match player.index() {
+        0 => active_gamepad.0,
+        index => gamepads
+            .iter()
+            .filter(|gamepad| Some(*gamepad) != active_gamepad.0)
+            .nth(index - 1),
+    }
+}
+
+/// Handle control of the player sprites with the left stick or the buttons of
+/// their gamepad bound in the [`InputMap`] (the d-pad by default). Runs after
+/// the keyboard handling and only overrides it while the gamepad is being
+/// used.
+pub(crate) fn handle_gamepad_for_character(
+    active_gamepad: Res<ActiveGamepad>,
+    gamepads: Res<Gamepads>,
+    axes: Res<Axis<GamepadAxis>>,
+    buttons: Res<Input<GamepadButton>>,
+    input_map: Res<InputMap>,
+    mut query: Query<(&mut Moveable, &Player)>,
+) {
+    for (mut moveable, player) in &mut query {
+        let Some(gamepad) = player_gamepad(player, &active_gamepad, &gamepads) else {
+            continue;
+        };
+
+        let stick = Vec2::new(
+            axes.get(GamepadAxis::new(gamepad, GamepadAxisType::LeftStickX))
+                .unwrap_or_default(),
+            axes.get(GamepadAxis::new(gamepad, GamepadAxisType::LeftStickY))
+                .unwrap_or_default(),
+        );
+        let d_pad =
+            movement_direction(|action| input_map.button_pressed(action, gamepad, &buttons));
+
+        // The d-pad wins over the stick, the stick keeps its analog value
+        let direction = if d_pad != Vec2::ZERO { d_pad } else { stick };
+        if direction != Vec2::ZERO {
+            moveable.set_direction(direction);
+        }
+    }
+}
+
+/// Handle muting and unmuting the music and sound effects with the gamepad
+/// button bound to [`InputAction::Mute`].
+pub(crate) fn mute_with_gamepad(
+    active_gamepad: Res<ActiveGamepad>,
+    buttons: Res<Input<GamepadButton>>,
+    input_map: Res<InputMap>,
+    mut settings: ResMut<Settings>,
+) {
+    let Some(gamepad) = active_gamepad.0 else {
+        return;
+    };
+
+    if input_map.button_just_pressed(InputAction::Mute, gamepad, &buttons) {
+        settings.muted = !settings.muted;
+    }
+}
@@ -0,0 +1,264 @@
+use bevy::{
+    ecs::system::Resource,
+    input::{
+        gamepad::{Gamepad, GamepadButton, GamepadButtonType},
+        keyboard::KeyCode,
+        Input,
+    },
+    utils::HashMap,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::persistence::{Location, Persisted};
+
+/// Logical actions the player can bind keys and gamepad buttons to.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Serialize, Deserialize)]
+pub(crate) enum InputAction {
+    MoveUp,
+    MoveDown,
+    MoveLeft,
+    MoveRight,
+    Mute,
+    Pause,
+    Confirm,
+}
+
+impl InputAction {
+    /// All actions, in the order they are listed on the controls screen.
+    pub(crate) const ALL: [InputAction; 7] = [
+        InputAction::MoveUp,
+        InputAction::MoveDown,
+        InputAction::MoveLeft,
+        InputAction::MoveRight,
+        InputAction::Mute,
+        InputAction::Pause,
+        InputAction::Confirm,
+    ];
+
+    /// Name of the action shown to the player.
+    pub(crate) fn label(&self) -> &'static str {
+        match self {
+            InputAction::MoveUp => "Up",
+            InputAction::MoveDown => "Down",
+            InputAction::MoveLeft => "Left",
+            InputAction::MoveRight => "Right",
+            InputAction::Mute => "Mute",
+            InputAction::Pause => "Pause",
+            InputAction::Confirm => "Confirm",
+        }
+    }
+}
+
+/// Keys and gamepad buttons bound to each [`InputAction`]. The first key and
+/// the first button of an action are the ones the player can rebind, the
+/// others (such as the arrow keys) are always available, except in two player
+/// games where the arrow keys move the second player.
+#[derive(Resource, Clone, Serialize, Deserialize)]
+pub(crate) struct InputMap {
+    keys: HashMap<InputAction, Vec<KeyCode>>,
+    buttons: HashMap<InputAction, Vec<GamepadButtonType>>,
+}
+
+impl Default for InputMap {
+    /// WASD and arrow keys, for QWERTY keyboards.
+    fn default() -> Self {
+        Self::with_movement_keys([KeyCode::W, KeyCode::S, KeyCode::A, KeyCode::D])
+    }
+}
+
+impl InputMap {
+    /// ZQSD and arrow keys, for AZERTY keyboards.
+    pub(crate) fn azerty() -> Self {
+        Self::with_movement_keys([KeyCode::Z, KeyCode::S, KeyCode::Q, KeyCode::D])
+    }
+
+    /// Default bindings using the given up, down, left and right keys next to
+    /// the arrow keys.
+    fn with_movement_keys([up, down, left, right]: [KeyCode; 4]) -> Self {
+        let keys = HashMap::from_iter([
+            (InputAction::MoveUp, vec![up, KeyCode::Up]),
+            (InputAction::MoveDown, vec![down, KeyCode::Down]),
+            (InputAction::MoveLeft, vec![left, KeyCode::Left]),
+            (InputAction::MoveRight, vec![right, KeyCode::Right]),
+            (InputAction::Mute, vec![KeyCode::M]),
+            (InputAction::Pause, vec![KeyCode::Escape]),
+            (InputAction::Confirm, vec![KeyCode::Return, KeyCode::Space]),
+        ]);
+        let buttons = HashMap::from_iter([
+            (InputAction::MoveUp, vec![GamepadButtonType::DPadUp]),
+            (InputAction::MoveDown, vec![GamepadButtonType::DPadDown]),
+            (InputAction::MoveLeft, vec![GamepadButtonType::DPadLeft]),
+            (InputAction::MoveRight, vec![GamepadButtonType::DPadRight]),
+            (InputAction::Mute, vec![GamepadButtonType::Select]),
+            (InputAction::Pause, vec![GamepadButtonType::Start]),
+            (InputAction::Confirm, vec![GamepadButtonType::South]),
+        ]);
+
+        Self { keys, buttons }
+    }
+
+    /// Returns the keys bound to the action.
+    pub(crate) fn keys(&self, action: InputAction) -> &[KeyCode] {
+        self.keys.get(&action).map_or(&[], Vec::as_slice)
+    }
+
+    /// Returns the gamepad buttons bound to the action.
+    pub(crate) fn buttons(&self, action: InputAction) -> &[GamepadButtonType] {
+        self.buttons.get(&action).map_or(&[], Vec::as_slice)
+    }
+
+    /// Bind the key to the action, in place of its first key. The key is
+    /// removed from any other action so it only ever does one thing. Returns
+    /// `false`, leaving the bindings as they were, if the key is the last one
+    /// bound to another action, as that action couldn't be used any more.
+    pub(crate) fn rebind_key(&mut self, action: InputAction, key: KeyCode) -> bool {
+        rebind(&mut self.keys, action, key)
+    }
+
+    /// Bind the gamepad button to the action, in place of its first button,
+    /// in the same way as [`InputMap::rebind_key`].
+    pub(crate) fn rebind_button(&mut self, action: InputAction, button: GamepadButtonType) -> bool {
+        rebind(&mut self.buttons, action, button)
+    }
+
+    /// Returns `true` while any key bound to the action is held down.
+    pub(crate) fn pressed(&self, action: InputAction, keyboard_input: &Input<KeyCode>) -> bool {
+        keyboard_input.any_pressed(self.keys(action).iter().copied())
+    }
+
+    /// Returns `true` while the first key bound to the action, the one the
+    /// player can rebind, is held down.
+    pub(crate) fn first_key_pressed(
+        &self,
+        action: InputAction,
+        keyboard_input: &Input<KeyCode>,
+    ) -> bool {
+        self.keys(action)
+            .first()
+            .is_some_and(|key| keyboard_input.pressed(*key))
+    }
+
+    /// Returns `true` if any key bound to the action was pressed this frame.
+    pub(crate) fn just_pressed(
+        &self,
+        action: InputAction,
+        keyboard_input: &Input<KeyCode>,
+    ) -> bool {
+        keyboard_input.any_just_pressed(self.keys(action).iter().copied())
+    }
+
+    /// Returns `true` while any button of the gamepad bound to the action is
+    /// held down.
+    pub(crate) fn button_pressed(
+        &self,
+        action: InputAction,
+        gamepad: Gamepad,
+        gamepad_input: &Input<GamepadButton>,
+    ) -> bool {
+        gamepad_input.any_pressed(self.gamepad_buttons(action, gamepad))
+    }
+
+    /// Returns `true` if any button of the gamepad bound to the action was
+    /// pressed this frame.
+    pub(crate) fn button_just_pressed(
+        &self,
+        action: InputAction,
+        gamepad: Gamepad,
+        gamepad_input: &Input<GamepadButton>,
+    ) -> bool {
+        gamepad_input.any_just_pressed(self.gamepad_buttons(action, gamepad))
+    }
+
+    fn gamepad_buttons(
+        &self,
+        action: InputAction,
+        gamepad: Gamepad,
+    ) -> impl Iterator<Item = GamepadButton> + '_ {
+        self.buttons
+            .get(&action)
+            .into_iter()
+            .flatten()
+            .map(move |button_type| GamepadButton::new(gamepad, *button_type))
+    }
+}
+
+/// Bind `input` to `action` in place of its first input, see
+/// [`InputMap::rebind_key`].
+fn rebind<T: Copy + PartialEq>(
+    bindings: &mut HashMap<InputAction, Vec<T>>,
+    action: InputAction,
+    input: T,
+) -> bool {
+    let last_of_another_action = bindings
+        .iter()
+        .any(|(bound_action, bound)| *bound_action != action && bound[..] == [input]);
+    if last_of_another_action {
+        return false;
+    }
+
+    for bound in bindings.values_mut() {
+        bound.retain(|bound| *bound != input);
+    }
+
+    let bound = bindings.entry(action).or_default();
+    if bound.is_empty() {
+        bound.push(input);
+    } else {
+        bound[0] = input;
+    }
+    true
+}
+
+impl Persisted for InputMap {
+    const FILE: &'static str = "input.ron";
+    const LOCATION: Location = Location::Config;
+    const VERSION: u32 = 1;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rebind_key_takes_the_key_from_another_action() {
+        let mut input_map = InputMap::default();
+
+        assert!(input_map.rebind_key(InputAction::MoveUp, KeyCode::Space));
+
+        assert_eq!(
+            input_map.keys(InputAction::MoveUp),
+            [KeyCode::Space, KeyCode::Up]
+        );
+        assert_eq!(input_map.keys(InputAction::Confirm), [KeyCode::Return]);
+    }
+
+    #[test]
+    fn rebind_key_leaves_another_action_its_last_key() {
+        let mut input_map = InputMap::default();
+
+        assert!(!input_map.rebind_key(InputAction::MoveUp, KeyCode::M));
+
+        assert_eq!(
+            input_map.keys(InputAction::MoveUp),
+            [KeyCode::W, KeyCode::Up]
+        );
+        assert_eq!(input_map.keys(InputAction::Mute), [KeyCode::M]);
+    }
+
+    #[test]
+    fn rebind_button() {
+        let mut input_map = InputMap::default();
+
+        assert!(input_map.rebind_button(InputAction::Mute, GamepadButtonType::North));
+        assert!(!input_map.rebind_button(InputAction::Mute, GamepadButtonType::Start));
+
+        assert_eq!(
+            input_map.buttons(InputAction::Mute),
+            [GamepadButtonType::North]
+        );
+        assert_eq!(
+            input_map.buttons(InputAction::Pause),
+            [GamepadButtonType::Start]
+        );
+    }
+}
@@ -0,0 +1,303 @@
+use std::time::Duration;
+
+use bevy::{
+    app::{Plugin, Update},
+    asset::{Assets, Handle},
+    ecs::{
+        bundle::Bundle,
+        component::Component,
+        query::{Added, With, Without},
+        schedule::{common_conditions::in_state, IntoSystemConfigs, OnEnter},
+        system::{Commands, EntityCommands, Query, Res, ResMut, Resource},
+    },
+    hierarchy::Parent,
+    log::warn,
+    math::{Rect, Vec2, Vec3},
+    sprite::TextureAtlas,
+    time::{Time, Timer, TimerMode},
+    transform::components::Transform,
+};
+use bevy_ecs_ldtk::prelude::GridCoords;
+use rand::Rng;
+use serde::Deserialize;
+
+use crate::{
+    ai::{Chase, Flee, Patrol, Wander},
+    characters::{BasicCharacter, SpriteSheet, SpriteSheetAtlases, Status},
+    level_config::{LevelConfig, LevelConfigHandle},
+    markers::{CharacterMarker, EnemyMarker, OnGameScreen},
+    moveable::{Moveable, Speed},
+    pause::PauseState,
+    setup::{
+        level_transform, EnemyStart, PlayerStart, CHARACTER_BOTTOM_BOUND, CHARACTER_LEFT_BOUND,
+        CHARACTER_RIGHT_BOUND, CHARACTER_TOP_BOUND,
+    },
+    tile_collision::{collider_rect_in_level, feet_rect, Collider},
+    GameState,
+};
+
+/// How fast enemies walk, in pixels per second.
+const ENEMY_SPEED: f32 = 60.;
+
+/// Enemies don't die, their health is only there for the animation and
+/// movement systems shared with the player.
+const ENEMY_HEALTH: u8 = 1;
+
+/// Enemies are never spawned closer than this to the player start.
+const MIN_DISTANCE_FROM_PLAYER: f32 = 120.;
+
+/// Number of random locations tried for each enemy before giving up on
+/// spawning it, in case the level has no free room left.
+const SPAWN_ATTEMPTS: usize = 100;
+
+/// Time after hurting the player before an enemy can hurt them again, so
+/// that standing in contact doesn't drain all their health at once.
+const CONTACT_COOLDOWN_SECONDS: f32 = 1.;
+
+/// How an enemy moves around the level.
+#[derive(Clone, Copy, Deserialize)]
+pub(crate) enum EnemyBehaviour {
+    /// Walk left and right across the given distance in pixels, centred on
+    /// the spawn point, turning around early when blocked.
+    Patrol(f32),
+    /// Walk in a random direction, changing direction or standing still
+    /// every few seconds.
+    Wander,
+    /// Walk towards the nearest player within the given sight radius in
+    /// pixels, around the walls and furniture.
+    Chase(f32),
+    /// Run away from the nearest player within the given sight radius in
+    /// pixels.
+    Flee(f32),
+}
+
+/// The health points an enemy removes from the player when touching them.
+#[derive(Component)]
+pub(crate) struct ContactDamage {
+    damage: u8,
+    cooldown: Timer,
+}
+
+impl ContactDamage {
+    /// Creates a new [`ContactDamage`], ready to hurt the player.
+    fn new(damage: u8) -> Self {
+        Self {
+            damage,
+            cooldown: Timer::new(Duration::ZERO, TimerMode::Once),
+        }
+    }
+
+    /// Returns the damage to do to the player touching the enemy, unless the
+    /// enemy has hurt them too recently.
+    pub(crate) fn hit(&mut self) -> Option<u8> {
+        if !self.cooldown.finished() {
+            return None;
+        }
+
+        self.cooldown = Timer::from_seconds(CONTACT_COOLDOWN_SECONDS, TimerMode::Once);
+        Some(self.damage)
+    }
+}
+
+/// This [`Bundle`] defines an enemy: a [`BasicCharacter`] with the
+/// [`EnemyMarker`] telling it apart from the player, and the damage it does
+/// on contact.
+#[derive(Bundle)]
+struct EnemyBundle {
+    basic_character: BasicCharacter,
+    status: Status,
+    enemy_marker: EnemyMarker,
+    contact_damage: ContactDamage,
+}
+
+/// Plugin spawning the enemies placed in the LDtk level and those listed in
+/// the [`LevelConfig`], with the [`crate::ai`] behaviour moving them.
+/// Contact with the player is handled by the
+/// [`crate::collision::CollisionHandler`].
+pub(crate) struct Enemies;
+
+impl Plugin for Enemies {
+    fn build(&self, app: &mut bevy::prelude::App) {
+        app.init_resource::<EnemiesSpawned>()
+            .add_systems(OnEnter(GameState::Game), reset_enemies_spawned)
+            .add_systems(
+                Update,
+                (
+                    setup_enemies,
+                    setup_placed_enemies,
+                    cool_down.run_if(in_state(PauseState::Running)),
+                )
+                    .run_if(in_state(GameState::Game)),
+            );
+    }
+}
+
+/// Whether the enemies of the [`LevelConfig`] and those placed in the LDtk
+/// level have been spawned this round, so that reloading the level doesn't
+/// add more of them.
+#[derive(Resource, Default)]
+struct EnemiesSpawned {
+    random: bool,
+    placed: bool,
+}
+
+/// No enemies have been spawned at the start of a round.
+fn reset_enemies_spawned(mut enemies_spawned: ResMut<EnemiesSpawned>) {
+    *enemies_spawned = EnemiesSpawned::default();
+}
+
+/// Spawn an enemy drawn with the flamingo [`SpriteSheet`] at `position`, in
+/// world space, returning it so its behaviour can be added.
+fn spawn_enemy<'w, 's, 'a>(
+    commands: &'a mut Commands<'w, 's>,
+    texture_atlas: Handle<TextureAtlas>,
+    position: Vec3,
+    damage: u8,
+) -> EntityCommands<'w, 's, 'a> {
+    commands.spawn((
+        EnemyBundle {
+            basic_character: BasicCharacter {
+                animated: SpriteSheet::Flamingo.animated(texture_atlas, position),
+                character_marker: CharacterMarker,
+                moveable: Moveable::new(Speed(ENEMY_SPEED)),
+            },
+            status: Status::new(ENEMY_HEALTH),
+            enemy_marker: EnemyMarker,
+            contact_damage: ContactDamage::new(damage),
+        },
+        OnGameScreen,
+    ))
+}
+
+/// Spawn the enemies of the [`LevelConfig`] at random locations once the
+/// LDtk level has spawned, away from the [`PlayerStart`], once per round.
+#[allow(clippy::too_many_arguments)]
+fn setup_enemies(
+    mut commands: Commands,
+    sprite_sheet_atlases: Res<SpriteSheetAtlases>,
+    level_config: Res<LevelConfigHandle>,
+    level_configs: Res<Assets<LevelConfig>>,
+    player_start_query: Query<(&Transform, &Parent), Added<PlayerStart>>,
+    level_query: Query<&Transform, Without<PlayerStart>>,
+    collider_query: Query<&GridCoords, With<Collider>>,
+    mut enemies_spawned: ResMut<EnemiesSpawned>,
+) {
+    let Some((start_transform, level)) = player_start_query.iter().next() else {
+        return;
+    };
+    if enemies_spawned.random {
+        return;
+    }
+    enemies_spawned.random = true;
+
+    // The player start and colliders are relative to their level, the enemies
+    // live in world space
+    let level_transform = level_transform(&level_query, level);
+    let start = level_transform.transform_point(start_transform.translation);
+    let colliders = collider_query
+        .iter()
+        .map(|grid_coords| {
+            let rect = collider_rect_in_level(*grid_coords);
+            Rect::from_center_size(
+                level_transform
+                    .transform_point(rect.center().extend(0.))
+                    .truncate(),
+                rect.size(),
+            )
+        })
+        .collect::<Vec<_>>();
+
+    let texture_atlas_handle = sprite_sheet_atlases.get(SpriteSheet::Flamingo);
+
+    let mut rng = rand::thread_rng();
+
+    for spawn in &level_config.get(&level_configs).enemies {
+        for _ in 0..spawn.count {
+            let position = (0..SPAWN_ATTEMPTS)
+                .map(|_| {
+                    Vec3::new(
+                        rng.gen_range(CHARACTER_LEFT_BOUND..CHARACTER_RIGHT_BOUND),
+                        rng.gen_range(CHARACTER_BOTTOM_BOUND..CHARACTER_TOP_BOUND),
+                        10.,
+                    )
+                })
+                .find(|position| {
+                    let feet = feet_rect(*position);
+                    position.truncate().distance(start.truncate()) >= MIN_DISTANCE_FROM_PLAYER
+                        && colliders
+                            .iter()
+                            .all(|collider| collider.intersect(feet).is_empty())
+                });
+            let Some(position) = position else {
+                warn!("No room left to spawn an enemy");
+                continue;
+            };
+
+            let mut enemy = spawn_enemy(
+                &mut commands,
+                texture_atlas_handle.clone(),
+                position,
+                spawn.damage,
+            );
+
+            match spawn.behaviour {
+                EnemyBehaviour::Patrol(distance) => enemy.insert(Patrol::new(vec![
+                    position.truncate() + Vec2::new(distance / 2., 0.),
+                    position.truncate() - Vec2::new(distance / 2., 0.),
+                ])),
+                EnemyBehaviour::Wander => enemy.insert(Wander::default()),
+                EnemyBehaviour::Chase(sight_radius) => enemy.insert(Chase::new(sight_radius)),
+                EnemyBehaviour::Flee(sight_radius) => enemy.insert(Flee { sight_radius }),
+            };
+        }
+    }
+}
+
+/// Spawn an enemy at each [`EnemyStart`] placed in the LDtk level, patrolling
+/// from there through its waypoints. Like [`setup_enemies`], this only
+/// happens once per round.
+fn setup_placed_enemies(
+    mut commands: Commands,
+    sprite_sheet_atlases: Res<SpriteSheetAtlases>,
+    enemy_start_query: Query<(&EnemyStart, &Transform, &Parent), Added<EnemyStart>>,
+    level_query: Query<&Transform, Without<EnemyStart>>,
+    mut enemies_spawned: ResMut<EnemiesSpawned>,
+) {
+    if enemy_start_query.is_empty() || enemies_spawned.placed {
+        return;
+    }
+    enemies_spawned.placed = true;
+
+    let texture_atlas_handle = sprite_sheet_atlases.get(SpriteSheet::Flamingo);
+
+    for (enemy_start, start_transform, level) in &enemy_start_query {
+        // The start and waypoints are relative to the level, the enemies live
+        // in world space
+        let level_transform = level_transform(&level_query, level);
+        let start = level_transform.transform_point(start_transform.translation);
+        let waypoints = std::iter::once(start)
+            .chain(
+                enemy_start
+                    .waypoints
+                    .iter()
+                    .map(|waypoint| level_transform.transform_point(waypoint.extend(0.))),
+            )
+            .map(|waypoint| waypoint.truncate())
+            .collect();
+
+        spawn_enemy(
+            &mut commands,
+            texture_atlas_handle.clone(),
+            Vec3::new(start.x, start.y, 10.),
+            enemy_start.damage,
+        )
+        .insert(Patrol::new(waypoints));
+    }
+}
+
+/// Count down the time until the enemies can hurt the player again.
+fn cool_down(time: Res<Time>, mut query: Query<&mut ContactDamage>) {
+    for mut contact_damage in &mut query {
+        contact_damage.cooldown.tick(time.delta());
+    }
+}
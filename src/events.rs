@@ -0,0 +1,63 @@
+use bevy::{
+    app::Plugin,
+    ecs::{entity::Entity, event::Event},
+};
+
+use crate::characters::Effect;
+
+/// Registers the gameplay events. Systems such as the scoreboard or audio
+/// can subscribe to these with an `EventReader` rather than polling the
+/// character's [`crate::characters::Status`] and
+/// [`crate::characters::Inventory`] every frame.
+pub(crate) struct GameEvents;
+
+impl Plugin for GameEvents {
+    fn build(&self, app: &mut bevy::prelude::App) {
+        app.add_event::<PresentCollected>()
+            .add_event::<PlayerDamaged>()
+            .add_event::<PlayerHealed>()
+            .add_event::<EffectStarted>()
+            .add_event::<PlayerDied>()
+            .add_event::<LevelWon>();
+    }
+}
+
+/// Sent when a character collects a nice present.
+#[derive(Event)]
+pub(crate) struct PresentCollected {
+    pub(crate) character: Entity,
+    /// Number of presents in the character's inventory, including this one.
+    pub(crate) total: u8,
+}
+
+/// Sent when a character is hurt by a naughty present or an enemy.
+#[derive(Event)]
+pub(crate) struct PlayerDamaged {
+    pub(crate) character: Entity,
+    /// Health left after the damage has been applied.
+    pub(crate) health: u8,
+}
+
+/// Sent when a character restores health with a healing present.
+#[derive(Event)]
+pub(crate) struct PlayerHealed {
+    pub(crate) character: Entity,
+    /// Health after the healing has been applied.
+    pub(crate) health: u8,
+}
+
+/// Sent when a present starts an [`Effect`] on a character, or starts its
+/// time over.
+#[derive(Event)]
+pub(crate) struct EffectStarted {
+    pub(crate) effect: Effect,
+}
+
+/// Sent once when a character's health runs out.
+#[derive(Event)]
+pub(crate) struct PlayerDied;
+
+/// Sent once per round, when the first character meets the win condition for
+/// the level.
+#[derive(Event)]
+pub(crate) struct LevelWon;
@@ -1,5 +1,315 @@
-use bevy::ecs::component::Component;
+use bevy::{
+    app::{Plugin, Startup, Update},
+    asset::{AssetServer, Handle},
+    audio::{
+        AudioBundle, AudioSink, AudioSinkPlayback, AudioSource, PlaybackMode, PlaybackSettings,
+        Volume,
+    },
+    ecs::{
+        change_detection::DetectChanges,
+        component::Component,
+        entity::Entity,
+        event::EventReader,
+        query::{Or, With},
+        schedule::{OnEnter, State},
+        system::{Commands, Query, Res, Resource},
+    },
+    time::Time,
+};
 
-/// Marker for entities that play audio.
+use crate::{
+    characters::Effect,
+    events::{EffectStarted, LevelWon, PlayerDamaged, PlayerDied, PlayerHealed, PresentCollected},
+    settings::{MusicTrack, Settings},
+    GameState,
+};
+
+/// Track played on the menu screens. The track played during rounds is the
+/// one selected in the [`Settings`].
+const MENU_TRACK: MusicTrack = MusicTrack::NaughtyNNice;
+
+/// Time taken by a track to fade in or out, in seconds.
+const FADE_SECONDS: f32 = 1.5;
+
+/// Whether a [`Music`] track is fading in or out.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Fade {
+    In,
+    Out,
+}
+
+/// Component of the entities playing a background music track. The track
+/// fading out is despawned once it is silent.
+#[derive(Component)]
+struct Music {
+    track: MusicTrack,
+    fade: Fade,
+    /// How far the track has faded in, from 0 (silent) to 1 (at the music
+    /// volume from the [`Settings`]).
+    level: f32,
+}
+
+/// Marker for the entities playing the tune heard at the end of a round,
+/// despawned once it has finished.
+#[derive(Component)]
+struct Stinger;
+
+/// Marker for the entities playing a one-shot sound effect, despawned once
+/// the sound has finished.
 #[derive(Component)]
-pub(crate) struct Audio;
+struct SoundEffect;
+
+/// Handles to the sound effects and stingers, loaded at startup so they are
+/// ready to play when first needed.
+#[derive(Resource)]
+struct SoundEffects {
+    present_nice: Handle<AudioSource>,
+    present_naughty: Handle<AudioSource>,
+    die: Handle<AudioSource>,
+    celebrate: Handle<AudioSource>,
+    win_stinger: Handle<AudioSource>,
+    lose_stinger: Handle<AudioSource>,
+}
+
+/// Plugin playing the background music for the current [`GameState`], a
+/// stinger at the end of each round and the sound effects for the gameplay
+/// events, at the volumes from the [`Settings`]. Muting the game mutes them
+/// all.
+pub(crate) struct GameAudio;
+
+impl Plugin for GameAudio {
+    fn build(&self, app: &mut bevy::prelude::App) {
+        app.add_systems(Startup, load_sound_effects)
+            .add_systems(OnEnter(GameState::LevelComplete), play_win_stinger)
+            .add_systems(OnEnter(GameState::Win), play_win_stinger)
+            .add_systems(OnEnter(GameState::Lose), play_lose_stinger)
+            .add_systems(
+                Update,
+                (
+                    select_music,
+                    fade_music,
+                    apply_mute,
+                    apply_sound_effect_volume,
+                    play_sound_effects,
+                ),
+            );
+    }
+}
+
+/// Load the [`SoundEffects`] at startup.
+fn load_sound_effects(asset_server: Res<AssetServer>, mut commands: Commands) {
+    commands.insert_resource(SoundEffects {
+        present_nice: asset_server.load("sounds/sfx/present_nice.wav"),
+        present_naughty: asset_server.load("sounds/sfx/present_naughty.wav"),
+        die: asset_server.load("sounds/sfx/die.wav"),
+        celebrate: asset_server.load("sounds/sfx/celebrate.wav"),
+        win_stinger: asset_server.load("sounds/win_stinger.wav"),
+        lose_stinger: asset_server.load("sounds/lose_stinger.wav"),
+    });
+}
+
+/// Returns the track to play in the given state, if any. There is no music at
+/// the end of a round, only the stinger.
+fn track_for_state(game_state: &GameState, settings: &Settings) -> Option<MusicTrack> {
+    match game_state {
+        GameState::Menu => Some(MENU_TRACK),
+        GameState::Game | GameState::Restarting => Some(settings.music_track),
+        GameState::LevelComplete | GameState::Win | GameState::Lose => None,
+    }
+}
+
+/// Crossfade to the track for the current [`GameState`] whenever the state or
+/// the [`Settings`] change. A track still fading out is faded back in rather
+/// than started again.
+fn select_music(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    game_state: Res<State<GameState>>,
+    settings: Res<Settings>,
+    mut music_query: Query<&mut Music>,
+) {
+    if !game_state.is_changed() && !settings.is_changed() {
+        return;
+    }
+
+    let track = track_for_state(game_state.get(), &settings);
+
+    let mut playing = false;
+    for mut music in &mut music_query {
+        if Some(music.track) == track && !playing {
+            music.fade = Fade::In;
+            playing = true;
+        } else {
+            music.fade = Fade::Out;
+        }
+    }
+
+    if let Some(track) = track.filter(|_| !playing) {
+        commands.spawn((
+            AudioBundle {
+                source: asset_server.load(track.path()),
+                settings: PlaybackSettings {
+                    mode: PlaybackMode::Loop,
+                    volume: Volume::new_relative(0.),
+                    paused: settings.muted,
+                    ..Default::default()
+                },
+            },
+            Music {
+                track,
+                fade: Fade::In,
+                level: 0.,
+            },
+        ));
+    }
+}
+
+/// Fade the music tracks in or out, despawning those that have faded out.
+/// The real time is used so that the music still fades while the game is
+/// paused.
+fn fade_music(
+    mut commands: Commands,
+    time: Res<Time>,
+    settings: Res<Settings>,
+    mut music_query: Query<(Entity, &mut Music, Option<&AudioSink>)>,
+) {
+    let step = time.raw_delta_seconds() / FADE_SECONDS;
+
+    for (entity, mut music, sink) in &mut music_query {
+        music.level = match music.fade {
+            Fade::In => (music.level + step).min(1.),
+            Fade::Out => (music.level - step).max(0.),
+        };
+
+        if music.fade == Fade::Out && music.level <= 0. {
+            commands.entity(entity).despawn();
+        } else if let Some(sink) = sink {
+            sink.set_volume(music.level * settings.music_volume);
+        }
+    }
+}
+
+/// Mute or unmute everything being played.
+fn apply_mute(
+    settings: Res<Settings>,
+    sink_query: Query<&AudioSink, Or<(With<Music>, With<Stinger>, With<SoundEffect>)>>,
+) {
+    if !settings.is_changed() {
+        return;
+    }
+
+    for sink in &sink_query {
+        if settings.muted {
+            sink.pause();
+        } else {
+            sink.play();
+        }
+    }
+}
+
+/// Set the volume of the sound effects being played.
+fn apply_sound_effect_volume(
+    settings: Res<Settings>,
+    sound_effect_query: Query<&AudioSink, With<SoundEffect>>,
+) {
+    if !settings.is_changed() {
+        return;
+    }
+
+    for sink in &sound_effect_query {
+        sink.set_volume(settings.sfx_volume);
+    }
+}
+
+/// Play the stinger for a round won.
+fn play_win_stinger(commands: Commands, sound_effects: Res<SoundEffects>, settings: Res<Settings>) {
+    play_stinger(commands, &sound_effects.win_stinger, &settings);
+}
+
+/// Play the stinger for a round lost.
+fn play_lose_stinger(
+    commands: Commands,
+    sound_effects: Res<SoundEffects>,
+    settings: Res<Settings>,
+) {
+    play_stinger(commands, &sound_effects.lose_stinger, &settings);
+}
+
+/// Play a stinger once at the music volume, over the music fading out.
+fn play_stinger(mut commands: Commands, stinger: &Handle<AudioSource>, settings: &Settings) {
+    if settings.muted {
+        return;
+    }
+
+    commands.spawn((
+        AudioBundle {
+            source: stinger.clone(),
+            settings: PlaybackSettings {
+                mode: PlaybackMode::Despawn,
+                volume: Volume::new_relative(settings.music_volume),
+                ..Default::default()
+            },
+        },
+        Stinger,
+    ));
+}
+
+/// Play a sound effect for each gameplay event: collecting a nice present or
+/// one that heals or starts an effect, being hurt by a naughty present, dying
+/// and winning the level.
+#[allow(clippy::too_many_arguments)]
+fn play_sound_effects(
+    mut commands: Commands,
+    sound_effects: Res<SoundEffects>,
+    settings: Res<Settings>,
+    mut present_collected_events: EventReader<PresentCollected>,
+    mut player_damaged_events: EventReader<PlayerDamaged>,
+    mut player_healed_events: EventReader<PlayerHealed>,
+    mut effect_started_events: EventReader<EffectStarted>,
+    mut player_died_events: EventReader<PlayerDied>,
+    mut level_won_events: EventReader<LevelWon>,
+) {
+    let sounds = present_collected_events
+        .iter()
+        .map(|_| &sound_effects.present_nice)
+        .chain(
+            player_healed_events
+                .iter()
+                .map(|_| &sound_effects.present_nice),
+        )
+        // Golden presents start the score multiplier, and are already heard
+        // as nice presents
+        .chain(
+            effect_started_events
+                .iter()
+                .filter(|event| event.effect != Effect::ScoreMultiplier)
+                .map(|_| &sound_effects.present_nice),
+        )
+        .chain(
+            player_damaged_events
+                .iter()
+                .map(|_| &sound_effects.present_naughty),
+        )
+        .chain(player_died_events.iter().map(|_| &sound_effects.die))
+        .chain(level_won_events.iter().map(|_| &sound_effects.celebrate))
+        .collect::<Vec<_>>();
+
+    // Events are still read while muted so they aren't played on unmuting
+    if settings.muted {
+        return;
+    }
+
+    for sound in sounds {
+        commands.spawn((
+            AudioBundle {
+                source: sound.clone(),
+                settings: PlaybackSettings {
+                    mode: PlaybackMode::Despawn,
+                    volume: Volume::new_relative(settings.sfx_volume),
+                    ..Default::default()
+                },
+            },
+            SoundEffect,
+        ));
+    }
+}
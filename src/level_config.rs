@@ -0,0 +1,214 @@
+use bevy::{
+    app::{Plugin, Startup},
+    asset::{AddAsset, AssetLoader, AssetServer, Assets, Handle, LoadContext, LoadedAsset},
+    ecs::system::{Commands, Res, Resource},
+    reflect::{TypePath, TypeUuid},
+    utils::BoxedFuture,
+};
+use serde::Deserialize;
+
+use crate::{
+    campaign::LEVEL_COUNT,
+    enemy::EnemyBehaviour,
+    present::{PresentBehaviour, PresentType},
+};
+
+/// Rules for a round, loaded from a `Level_<index>.level.ron` file under
+/// `assets/levels/`. Edits to the file are picked up by the asset server
+/// while the game is running and apply from the next round.
+///
+/// The fields after `time_limit` can be left out. For example:
+///
+/// ```ron
+/// (
+///     starting_health: 100,
+///     win_target: 7,
+///     time_limit: Some(60.0),
+///     presents: [
+///         (present_type: Naughty(20), count: 2),
+///         (present_type: Healing(20), count: 1),
+///         (present_type: Golden, count: 1),
+///     ],
+///     present_behaviours: (
+///         nice: [Hop(80.0), Teleport(10.0)],
+///         naughty: [Drift(120.0)],
+///     ),
+///     enemies: [
+///         (behaviour: Chase(150.0), damage: 10, count: 1),
+///         (behaviour: Wander, damage: 10, count: 2),
+///     ],
+/// )
+/// ```
+///
+/// See [`PresentType`], [`PresentBehaviour`] and [`EnemyBehaviour`] for what
+/// each does.
+#[derive(Deserialize, TypeUuid, TypePath, Clone)]
+#[uuid = "9b7ea3c4-3d0e-4c36-a6f6-6f1f1e0d6a51"]
+pub(crate) struct LevelConfig {
+    /// Health the player starts the round with.
+    pub(crate) starting_health: u8,
+    /// Number of nice presents to collect to win the round.
+    pub(crate) win_target: u8,
+    /// Length of the round in seconds, if the round is timed.
+    pub(crate) time_limit: Option<f32>,
+    /// Extra presents to spawn at random locations at the start of the round,
+    /// on top of those placed in the LDtk level.
+    #[serde(default)]
+    pub(crate) presents: Vec<PresentSpawn>,
+    /// How the presents of each type move around the level, whether placed
+    /// in the LDtk level or spawned.
+    #[serde(default)]
+    pub(crate) present_behaviours: PresentBehaviours,
+    /// Enemies to spawn at random locations at the start of the round, on top
+    /// of those placed in the LDtk level.
+    #[serde(default)]
+    pub(crate) enemies: Vec<EnemySpawn>,
+}
+
+/// Number of presents of the given [`PresentType`] to spawn. The damage
+/// done by naughty presents is part of the type, e.g. `Naughty(20)`, as is
+/// the health restored by healing ones, e.g. `Healing(20)`.
+#[derive(Deserialize, Clone)]
+pub(crate) struct PresentSpawn {
+    pub(crate) present_type: PresentType,
+    pub(crate) count: u8,
+}
+
+/// The [`PresentBehaviour`]s of the presents of each type. Presents without
+/// any stay where they are until picked up.
+#[derive(Deserialize, Clone, Default)]
+pub(crate) struct PresentBehaviours {
+    #[serde(default)]
+    pub(crate) nice: Vec<PresentBehaviour>,
+    #[serde(default)]
+    pub(crate) naughty: Vec<PresentBehaviour>,
+    #[serde(default)]
+    pub(crate) healing: Vec<PresentBehaviour>,
+    #[serde(default)]
+    pub(crate) speed_boost: Vec<PresentBehaviour>,
+    #[serde(default)]
+    pub(crate) shield: Vec<PresentBehaviour>,
+    #[serde(default)]
+    pub(crate) golden: Vec<PresentBehaviour>,
+}
+
+impl PresentBehaviours {
+    /// Returns the behaviours of the presents of the given type.
+    pub(crate) fn for_type(&self, present_type: PresentType) -> &[PresentBehaviour] {
+        match present_type {
+            PresentType::Naughty(_) => &self.naughty,
+            PresentType::Nice => &self.nice,
+            PresentType::Healing(_) => &self.healing,
+            PresentType::SpeedBoost => &self.speed_boost,
+            PresentType::Shield => &self.shield,
+            PresentType::Golden => &self.golden,
+        }
+    }
+}
+
+/// Number of enemies with the given [`EnemyBehaviour`] to spawn, and the
+/// health points each removes from the player on contact.
+#[derive(Deserialize, Clone)]
+pub(crate) struct EnemySpawn {
+    pub(crate) behaviour: EnemyBehaviour,
+    pub(crate) damage: u8,
+    pub(crate) count: u8,
+}
+
+impl Default for LevelConfig {
+    /// Rules used until the level file has been loaded: five nice presents
+    /// to collect, with only the presents placed in the LDtk level, staying
+    /// where they are, and no enemies.
+    fn default() -> Self {
+        Self {
+            starting_health: 100,
+            win_target: 5,
+            time_limit: None,
+            presents: Vec::new(),
+            present_behaviours: PresentBehaviours::default(),
+            enemies: Vec::new(),
+        }
+    }
+}
+
+/// Handle to the [`LevelConfig`] of the current level, along with the
+/// default rules used until its file has loaded.
+#[derive(Resource)]
+pub(crate) struct LevelConfigHandle {
+    pub(crate) handle: Handle<LevelConfig>,
+    default: LevelConfig,
+}
+
+/// Handles to the [`LevelConfig`] of every level in the campaign, keeping them
+/// loaded.
+#[derive(Resource)]
+pub(crate) struct LevelConfigHandles(Vec<Handle<LevelConfig>>);
+
+impl LevelConfigHandles {
+    /// Returns the handle to the [`LevelConfig`] of the given level.
+    pub(crate) fn handle(&self, level: usize) -> Handle<LevelConfig> {
+        self.0[level].clone()
+    }
+}
+
+impl LevelConfigHandle {
+    /// Creates a [`LevelConfigHandle`] falling back on the default rules
+    /// until the file of `handle` has loaded.
+    pub(crate) fn new(handle: Handle<LevelConfig>) -> Self {
+        Self {
+            handle,
+            default: LevelConfig::default(),
+        }
+    }
+
+    /// Returns the loaded [`LevelConfig`], or the default rules if the file
+    /// hasn't finished loading yet.
+    pub(crate) fn get<'a>(&'a self, level_configs: &'a Assets<LevelConfig>) -> &'a LevelConfig {
+        level_configs.get(&self.handle).unwrap_or(&self.default)
+    }
+}
+
+/// Loads [`LevelConfig`] assets from `.level.ron` files.
+#[derive(Default)]
+struct LevelConfigLoader;
+
+impl AssetLoader for LevelConfigLoader {
+    fn load<'a>(
+        &'a self,
+        bytes: &'a [u8],
+        load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<(), bevy::asset::Error>> {
+        Box::pin(async move {
+            let level_config = ron::de::from_bytes::<LevelConfig>(bytes)?;
+            load_context.set_default_asset(LoadedAsset::new(level_config));
+            Ok(())
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["level.ron"]
+    }
+}
+
+/// Plugin registering the [`LevelConfig`] asset and loading the rules for
+/// every level at startup.
+pub(crate) struct LevelConfigPlugin;
+
+impl Plugin for LevelConfigPlugin {
+    fn build(&self, app: &mut bevy::prelude::App) {
+        app.add_asset::<LevelConfig>()
+            .init_asset_loader::<LevelConfigLoader>()
+            .add_systems(Startup, load_level_config);
+    }
+}
+
+/// Start loading the level rules and keep hold of the handles, starting with
+/// the first level selected.
+fn load_level_config(mut commands: Commands, asset_server: Res<AssetServer>) {
+    let handles = (0..LEVEL_COUNT)
+        .map(|level| asset_server.load(format!("levels/Level_{level}.level.ron")))
+        .collect::<Vec<_>>();
+
+    commands.insert_resource(LevelConfigHandle::new(handles[0].clone()));
+    commands.insert_resource(LevelConfigHandles(handles));
+}
@@ -1,18 +1,29 @@
 #![allow(clippy::type_complexity)]
 
-use bevy::{prelude::*, window::WindowResolution};
+use bevy::{asset::ChangeWatcher, prelude::*, utils::Duration, window::WindowResolution};
 use setup::InitialSetup;
 
+mod ai;
 mod animation;
+mod campaign;
 mod characters;
 mod collision;
 mod control_input;
+mod enemy;
+mod events;
 mod game_audio;
+mod level_config;
 mod markers;
 mod menu;
 mod moveable;
+mod pause;
+mod persistence;
+mod play_mode;
 mod present;
+mod score;
+mod settings;
 mod setup;
+mod tile_collision;
 
 pub(crate) const WINDOW_WIDTH: f32 = 480.;
 pub(crate) const WINDOW_HEIGHT: f32 = 288.;
@@ -23,7 +34,11 @@ pub(crate) enum GameState {
     Game,
     #[default]
     Menu,
+    LevelComplete,
     Win,
+    Lose,
+    // Passed through on the way back into `Game` to restart the round
+    Restarting,
 }
 
 fn main() {
@@ -40,6 +55,11 @@ fn main() {
                     }),
                     ..Default::default()
                 })
+                .set(AssetPlugin {
+                    // Reload assets such as the level rules when they change on disk
+                    watch_for_changes: ChangeWatcher::with_delay(Duration::from_millis(200)),
+                    ..Default::default()
+                })
                 .set(
                     // This sets image filtering to nearest
                     // This is done to prevent textures with low resolution (e.g. pixel art) from being blurred
@@ -7,3 +7,13 @@ pub(crate) struct CameraMarker;
 /// the player or an NPC or an enemy.
 #[derive(Component, Clone)]
 pub(crate) struct CharacterMarker;
+
+/// Marker for the characters that are enemies of the players, who have a
+/// [`crate::characters::Player`] instead. Both have a [`CharacterMarker`].
+#[derive(Component, Clone)]
+pub(crate) struct EnemyMarker;
+
+/// Marker for entities that only exist while a round is being played. They
+/// are despawned when leaving [`crate::GameState::Game`].
+#[derive(Component)]
+pub(crate) struct OnGameScreen;
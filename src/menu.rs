@@ -1,26 +1,108 @@
-// Display a start menu
+// Display a start menu, the play mode and level select screens, the high
+// scores, settings and controls screens, the pause menu and the end of round
+// screens
 
-use bevy::{app::AppExit, prelude::*};
+use bevy::{app::AppExit, ecs::system::EntityCommands, prelude::*};
 
-use crate::GameState;
+use crate::{
+    campaign::{Campaign, LEVEL_COUNT},
+    control_input::{ActiveGamepad, InputAction, InputMap},
+    pause::PauseState,
+    play_mode::PlayMode,
+    score::{check_high_score, HighScores, PendingHighScore, MAX_NAME_LENGTH},
+    settings::Settings,
+    setup::RoundResult,
+    GameState,
+};
 
 // consts
 
 const TEXT_COLOR: Color = Color::rgb(0.5, 0.5, 0.5);
+const WIN_TEXT_COLOR: Color = Color::rgb(0.2, 0.2, 0.9);
+const LOSE_TEXT_COLOR: Color = Color::rgb(0.9, 0.2, 0.5);
+const STATS_TEXT_COLOR: Color = Color::rgb(0.1, 0.1, 0.1);
+const LOCKED_TEXT_COLOR: Color = Color::rgb(0.3, 0.3, 0.3);
+
+const WIN_SPLASH_TINT: Color = Color::WHITE;
+const LOSE_SPLASH_TINT: Color = Color::rgb(1.0, 0.6, 0.6);
 
 const NORMAL_BUTTON: Color = Color::rgb(0.15, 0.15, 0.15);
 const HOVERED_BUTTON: Color = Color::rgb(0.25, 0.25, 0.25);
 const HOVERED_PRESSED_BUTTON: Color = Color::rgb(0.25, 0.65, 0.25);
 const PRESSED_BUTTON: Color = Color::rgb(0.35, 0.75, 0.35);
+const LOCKED_BUTTON: Color = Color::rgb(0.1, 0.1, 0.1);
+
+const PAUSE_OVERLAY: Color = Color::rgba(0.0, 0.0, 0.0, 0.6);
 
 // Tag component used to mark which setting is currently selected
 #[derive(Component)]
 struct SelectedOption;
 
-// All actions that can be triggered from a button click
+// Tag component used to mark the button selected with the keyboard or gamepad
+#[derive(Component)]
+struct NavigationFocus;
+
+// Sent when the button selected with the keyboard or gamepad is confirmed
+#[derive(Event)]
+struct NavigationConfirm(MenuButtonAction);
+
+// The action waiting for a key or gamepad button to be pressed on the controls
+// screen
+#[derive(Resource, Default)]
+struct AwaitingRebind(Option<InputAction>);
+
+// Tag component used to mark the text showing the key and button bound to an
+// action
+#[derive(Component)]
+struct BindingLabel(InputAction);
+
+// Tag component used to mark the text showing the name typed for a new high
+// score
+#[derive(Component)]
+struct HighScoreNameLabel;
+
+// Tag component used to mark the text showing the value of a setting
 #[derive(Component)]
+struct SettingLabel(Setting);
+
+// The settings that can be changed on the settings screen
+#[derive(Clone, Copy)]
+enum Setting {
+    MusicVolume,
+    SfxVolume,
+    MusicTrack,
+    WindowScale,
+}
+
+// All actions that can be triggered from a button click
+#[derive(Component, Clone, Copy)]
 enum MenuButtonAction {
+    // Start a new campaign from the first level
+    NewGame,
+    PlayModeSelect,
+    // Start a new campaign from the first level in the given mode
+    NewGameIn(PlayMode),
+    // Play the current level of the campaign
     Play,
+    // Start a new campaign from the given (unlocked) level
+    PlayLevel(usize),
+    LevelSelect,
+    HighScores,
+    Settings,
+    // Lower or raise the setting by the given number of steps
+    StepSetting(Setting, i8),
+    Controls,
+    // Carry on with the paused round
+    Resume,
+    // Start the paused round again from the beginning
+    Restart,
+    // Wait for the next key pressed and bind it to the action
+    Rebind(InputAction),
+    QwertyBindings,
+    AzertyBindings,
+    BackToSettings,
+    BackToMainMenu,
+    MainMenu,
     Quit,
 }
 
@@ -29,9 +111,16 @@ enum MenuButtonAction {
 enum MenuState {
     #[default]
     Main,
+    PlayModeSelect,
+    LevelSelect,
+    HighScores,
+    Settings,
+    Controls,
+    Pause,
     Disabled,
 }
 
+#[allow(clippy::too_many_arguments)]
 fn menu_action(
     interaction_query: Query<
         (&Interaction, &MenuButtonAction),
@@ -40,19 +129,197 @@ fn menu_action(
     mut app_exit_events: EventWriter<AppExit>,
     mut menu_state: ResMut<NextState<MenuState>>,
     mut game_state: ResMut<NextState<GameState>>,
+    mut campaign: ResMut<Campaign>,
+    mut navigation_confirm_events: EventReader<NavigationConfirm>,
+    mut input_map: ResMut<InputMap>,
+    mut awaiting_rebind: ResMut<AwaitingRebind>,
+    current_pause_state: Res<State<PauseState>>,
+    mut pause_state: ResMut<NextState<PauseState>>,
+    mut settings: ResMut<Settings>,
+    mut play_mode: ResMut<PlayMode>,
 ) {
-    for (interaction, menu_button_action) in &interaction_query {
-        if *interaction == Interaction::Pressed {
-            match menu_button_action {
-                MenuButtonAction::Quit => {
-                    app_exit_events.send(AppExit);
-                }
-                MenuButtonAction::Play => {
-                    game_state.set(GameState::Game);
-                    menu_state.set(MenuState::Disabled);
+    let pressed_actions = interaction_query
+        .iter()
+        .filter(|(interaction, _)| **interaction == Interaction::Pressed)
+        .map(|(_, menu_button_action)| *menu_button_action)
+        .chain(navigation_confirm_events.iter().map(|event| event.0));
+
+    for menu_button_action in pressed_actions {
+        match menu_button_action {
+            MenuButtonAction::Quit => {
+                app_exit_events.send(AppExit);
+            }
+            MenuButtonAction::NewGame => {
+                campaign.start_at(0);
+                game_state.set(GameState::Game);
+                menu_state.set(MenuState::Disabled);
+            }
+            MenuButtonAction::PlayModeSelect => {
+                menu_state.set(MenuState::PlayModeSelect);
+            }
+            MenuButtonAction::NewGameIn(mode) => {
+                *play_mode = mode;
+                campaign.start_at(0);
+                game_state.set(GameState::Game);
+                menu_state.set(MenuState::Disabled);
+            }
+            MenuButtonAction::Play => {
+                game_state.set(GameState::Game);
+                menu_state.set(MenuState::Disabled);
+            }
+            MenuButtonAction::PlayLevel(level) => {
+                campaign.start_at(level);
+                game_state.set(GameState::Game);
+                menu_state.set(MenuState::Disabled);
+            }
+            MenuButtonAction::LevelSelect => {
+                menu_state.set(MenuState::LevelSelect);
+            }
+            MenuButtonAction::HighScores => {
+                menu_state.set(MenuState::HighScores);
+            }
+            MenuButtonAction::Settings => {
+                menu_state.set(MenuState::Settings);
+            }
+            MenuButtonAction::StepSetting(setting, steps) => match setting {
+                Setting::MusicVolume => settings.step_music_volume(steps),
+                Setting::SfxVolume => settings.step_sfx_volume(steps),
+                Setting::MusicTrack => settings.toggle_music_track(),
+                Setting::WindowScale => settings.step_window_scale(steps),
+            },
+            MenuButtonAction::Controls => {
+                menu_state.set(MenuState::Controls);
+            }
+            MenuButtonAction::Resume => {
+                pause_state.set(PauseState::Running);
+                menu_state.set(MenuState::Disabled);
+            }
+            MenuButtonAction::Restart => {
+                game_state.set(GameState::Restarting);
+                menu_state.set(MenuState::Disabled);
+            }
+            MenuButtonAction::Rebind(action) => {
+                awaiting_rebind.0 = Some(action);
+            }
+            MenuButtonAction::QwertyBindings => {
+                *input_map = InputMap::default();
+            }
+            MenuButtonAction::AzertyBindings => {
+                *input_map = InputMap::azerty();
+            }
+            MenuButtonAction::BackToSettings => {
+                menu_state.set(MenuState::Settings);
+            }
+            MenuButtonAction::BackToMainMenu => {
+                // Screens opened from the pause menu go back to it
+                if *current_pause_state.get() == PauseState::Paused {
+                    menu_state.set(MenuState::Pause);
+                } else {
+                    menu_state.set(MenuState::Main);
                 }
             }
+            MenuButtonAction::MainMenu => {
+                game_state.set(GameState::Menu);
+            }
+        }
+    }
+}
+
+// This system lets the keyboard and the active gamepad move the focus between
+// the buttons of the current screen with the up and down bindings, and press
+// the focused button with the confirm binding
+#[allow(clippy::too_many_arguments)]
+fn menu_navigation(
+    mut commands: Commands,
+    keyboard_input: Res<Input<KeyCode>>,
+    active_gamepad: Res<ActiveGamepad>,
+    gamepad_buttons: Res<Input<GamepadButton>>,
+    input_map: Res<InputMap>,
+    awaiting_rebind: Res<AwaitingRebind>,
+    pending_high_score: Res<PendingHighScore>,
+    mut button_query: Query<
+        (
+            Entity,
+            &MenuButtonAction,
+            &GlobalTransform,
+            &mut BackgroundColor,
+            Option<&NavigationFocus>,
+        ),
+        With<Button>,
+    >,
+    mut navigation_confirm_events: EventWriter<NavigationConfirm>,
+) {
+    // The keys pressed are being captured for a new binding
+    if awaiting_rebind.0.is_some() {
+        return;
+    }
+    // The keys pressed are being typed as a name, only the gamepad navigates
+    let keyboard_captured = pending_high_score.0.is_some();
+    let just_pressed = |action| {
+        (!keyboard_captured && input_map.just_pressed(action, &keyboard_input))
+            || active_gamepad.0.is_some_and(|gamepad| {
+                input_map.button_just_pressed(action, gamepad, &gamepad_buttons)
+            })
+    };
+
+    // Buttons from the top to the bottom of the screen, and from left to right
+    // within a row
+    let mut buttons = button_query
+        .iter()
+        .map(|(entity, action, transform, _, focus)| {
+            (entity, *action, transform.translation(), focus.is_some())
+        })
+        .collect::<Vec<_>>();
+    if buttons.is_empty() {
+        return;
+    }
+    buttons.sort_by(|a, b| a.2.y.total_cmp(&b.2.y).then(a.2.x.total_cmp(&b.2.x)));
+    let focused = buttons.iter().position(|button| button.3);
+
+    if just_pressed(InputAction::Confirm) {
+        if let Some(focused) = focused {
+            navigation_confirm_events.send(NavigationConfirm(buttons[focused].1));
         }
+        return;
+    }
+
+    let new_focus = if just_pressed(InputAction::MoveDown) {
+        focused.map_or(0, |focused| (focused + 1) % buttons.len())
+    } else if just_pressed(InputAction::MoveUp) {
+        focused.map_or(0, |focused| (focused + buttons.len() - 1) % buttons.len())
+    } else {
+        return;
+    };
+
+    for (index, (entity, ..)) in buttons.iter().enumerate() {
+        let Ok((.., mut color, _)) = button_query.get_mut(*entity) else {
+            continue;
+        };
+        if index == new_focus {
+            commands.entity(*entity).insert(NavigationFocus);
+            *color = HOVERED_BUTTON.into();
+        } else {
+            commands.entity(*entity).remove::<NavigationFocus>();
+            *color = NORMAL_BUTTON.into();
+        }
+    }
+}
+
+// This system resumes the round when the pause binding is pressed again on
+// the pause menu, as if the Resume button had been pressed
+fn resume_with_pause_binding(
+    keyboard_input: Res<Input<KeyCode>>,
+    active_gamepad: Res<ActiveGamepad>,
+    gamepad_buttons: Res<Input<GamepadButton>>,
+    input_map: Res<InputMap>,
+    mut navigation_confirm_events: EventWriter<NavigationConfirm>,
+) {
+    let gamepad_pressed = active_gamepad.0.is_some_and(|gamepad| {
+        input_map.button_just_pressed(InputAction::Pause, gamepad, &gamepad_buttons)
+    });
+
+    if gamepad_pressed || input_map.just_pressed(InputAction::Pause, &keyboard_input) {
+        navigation_confirm_events.send(NavigationConfirm(MenuButtonAction::Resume));
     }
 }
 
@@ -77,6 +344,34 @@ fn button_system(
 #[derive(Component)]
 struct OnMainMenuScreen;
 
+// Tag component used to tag entities added on the play mode select screen
+#[derive(Component)]
+struct OnPlayModeSelectScreen;
+
+// Tag component used to tag entities added on the level select screen
+#[derive(Component)]
+struct OnLevelSelectScreen;
+
+// Tag component used to tag entities added on the high scores screen
+#[derive(Component)]
+struct OnHighScoresScreen;
+
+// Tag component used to tag entities added on the settings screen
+#[derive(Component)]
+struct OnSettingsScreen;
+
+// Tag component used to tag entities added on the controls screen
+#[derive(Component)]
+struct OnControlsScreen;
+
+// Tag component used to tag entities added on the pause menu
+#[derive(Component)]
+struct OnPauseScreen;
+
+// Tag component used to tag entities added on the end of round screens
+#[derive(Component)]
+struct OnEndScreen;
+
 pub struct MenuPlugin;
 
 impl Plugin for MenuPlugin {
@@ -86,10 +381,96 @@ impl Plugin for MenuPlugin {
             // Systems to handle the main menu screen
             .add_systems(OnEnter(MenuState::Main), main_menu_setup)
             .add_systems(OnExit(MenuState::Main), despawn_screen::<OnMainMenuScreen>)
+            // Systems to handle the play mode select screen
+            .add_systems(OnEnter(MenuState::PlayModeSelect), play_mode_select_setup)
+            .add_systems(
+                OnExit(MenuState::PlayModeSelect),
+                despawn_screen::<OnPlayModeSelectScreen>,
+            )
+            // Systems to handle the level select screen
+            .add_systems(OnEnter(MenuState::LevelSelect), level_select_setup)
+            .add_systems(
+                OnExit(MenuState::LevelSelect),
+                despawn_screen::<OnLevelSelectScreen>,
+            )
+            // Systems to handle the high scores screen
+            .add_systems(OnEnter(MenuState::HighScores), high_scores_setup)
+            .add_systems(
+                OnExit(MenuState::HighScores),
+                despawn_screen::<OnHighScoresScreen>,
+            )
+            // Systems to handle the settings screen
+            .add_systems(OnEnter(MenuState::Settings), settings_setup)
+            .add_systems(
+                OnExit(MenuState::Settings),
+                despawn_screen::<OnSettingsScreen>,
+            )
+            .add_systems(
+                Update,
+                update_setting_labels
+                    .after(menu_action)
+                    .run_if(in_state(MenuState::Settings)),
+            )
+            // Systems to handle the controls screen
+            .init_resource::<AwaitingRebind>()
+            .add_systems(OnEnter(MenuState::Controls), controls_setup)
+            .add_systems(
+                OnExit(MenuState::Controls),
+                (despawn_screen::<OnControlsScreen>, cancel_rebind),
+            )
+            .add_systems(
+                Update,
+                (
+                    capture_rebind.before(menu_navigation),
+                    update_binding_labels.after(menu_action),
+                )
+                    .run_if(in_state(MenuState::Controls)),
+            )
+            // Systems to handle the pause menu
+            .add_systems(OnEnter(PauseState::Paused), pause_menu_open)
+            .add_systems(OnEnter(MenuState::Pause), pause_menu_setup)
+            .add_systems(OnExit(MenuState::Pause), despawn_screen::<OnPauseScreen>)
+            .add_systems(
+                Update,
+                resume_with_pause_binding
+                    .before(menu_action)
+                    .run_if(in_state(MenuState::Pause)),
+            )
+            // Systems to handle the end of round screens
+            .add_systems(
+                OnEnter(GameState::LevelComplete),
+                level_complete_screen_setup.after(check_high_score),
+            )
+            .add_systems(
+                OnExit(GameState::LevelComplete),
+                despawn_screen::<OnEndScreen>,
+            )
+            .add_systems(
+                OnEnter(GameState::Win),
+                win_screen_setup.after(check_high_score),
+            )
+            .add_systems(OnExit(GameState::Win), despawn_screen::<OnEndScreen>)
+            .add_systems(OnEnter(GameState::Lose), lose_screen_setup)
+            .add_systems(OnExit(GameState::Lose), despawn_screen::<OnEndScreen>)
+            .add_systems(
+                Update,
+                (
+                    // After the navigation, so that Return doesn't also press a button
+                    type_high_score_name.after(menu_navigation),
+                    update_high_score_name_label,
+                )
+                    .chain(),
+            )
             // Common systems to all screens that handles buttons behavior
+            .add_event::<NavigationConfirm>()
             .add_systems(
                 Update,
-                (menu_action, button_system).run_if(in_state(GameState::Menu)),
+                (
+                    menu_navigation.before(menu_action),
+                    menu_action,
+                    button_system,
+                )
+                    .run_if(not(in_state(GameState::Game)).or_else(in_state(PauseState::Paused))),
             );
     }
 }
@@ -98,18 +479,112 @@ fn menu_setup(mut menu_state: ResMut<NextState<MenuState>>) {
     menu_state.set(MenuState::Main);
 }
 
+fn pause_menu_open(mut menu_state: ResMut<NextState<MenuState>>) {
+    menu_state.set(MenuState::Pause);
+}
+
 // Generic system that takes a component as a parameter, and will despawn all entities with that component
-fn despawn_screen<T: Component>(to_despawn: Query<Entity, With<T>>, mut commands: Commands) {
+pub(crate) fn despawn_screen<T: Component>(
+    to_despawn: Query<Entity, With<T>>,
+    mut commands: Commands,
+) {
     for entity in &to_despawn {
         commands.entity(entity).despawn_recursive();
     }
 }
 
+// Flexbox settings laying out the contents of a screen in a column, spread out
+// over the splash bitmap
+fn spread_column() -> Style {
+    Style {
+        justify_content: JustifyContent::SpaceAround,
+        flex_direction: FlexDirection::Column,
+        ..default()
+    }
+}
+
+// Flexbox settings laying out the contents of a screen in a column, centred on
+// the splash bitmap
+fn centred_column() -> Style {
+    Style {
+        justify_content: JustifyContent::Center,
+        align_items: AlignItems::Center,
+        flex_direction: FlexDirection::Column,
+        ..default()
+    }
+}
+
+// Spawn a screen tagged with `marker`, showing the splash bitmap with the
+// contents added by `spawn_contents` laid out over it with `layout`. Returns
+// the splash bitmap, for the screens that tint it or draw it over the
+// scoreboard.
+fn spawn_splash(
+    commands: &mut Commands,
+    asset_server: &AssetServer,
+    marker: impl Component,
+    layout: Style,
+    spawn_contents: impl FnOnce(&mut ChildBuilder),
+) -> Entity {
+    let splash = commands
+        .spawn(ImageBundle {
+            style: Style {
+                align_self: AlignSelf::End,
+                width: Val::Px(480.),
+                height: Val::Px(288.),
+                ..layout
+            },
+            image: UiImage::new(asset_server.load("images/splash.png")),
+            ..default()
+        })
+        .with_children(spawn_contents)
+        .id();
+
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    align_self: AlignSelf::Stretch,
+                    align_items: AlignItems::Start,
+                    justify_content: JustifyContent::Center,
+                    ..default()
+                },
+                ..default()
+            },
+            marker,
+        ))
+        .add_child(splash);
+
+    splash
+}
+
+// Spawn a button pressing `action`, showing `label`. Returns the button so
+// that more can be added to it.
+fn spawn_button<'w, 's, 'a>(
+    parent: &'a mut ChildBuilder<'w, 's, '_>,
+    style: &Style,
+    action: MenuButtonAction,
+    label: impl Into<String>,
+    text_style: &TextStyle,
+) -> EntityCommands<'w, 's, 'a> {
+    let mut button = parent.spawn((
+        ButtonBundle {
+            style: style.clone(),
+            background_color: NORMAL_BUTTON.into(),
+            ..default()
+        },
+        action,
+    ));
+    button.with_children(|parent| {
+        parent.spawn(TextBundle::from_section(label, text_style.clone()));
+    });
+    button
+}
+
 fn main_menu_setup(mut commands: Commands, asset_server: Res<AssetServer>) {
     // Common style for all buttons on the screen
     let button_style = Style {
         width: Val::Percent(50.0),
-        height: Val::Percent(25.0),
+        height: Val::Percent(13.0),
         margin: UiRect::all(Val::Px(2.0)),
         justify_content: JustifyContent::Center,
         align_items: AlignItems::Center,
@@ -130,95 +605,760 @@ fn main_menu_setup(mut commands: Commands, asset_server: Res<AssetServer>) {
         ..default()
     };
 
+    spawn_splash(
+        &mut commands,
+        &asset_server,
+        OnMainMenuScreen,
+        spread_column(),
+        |parent| {
+            // Display the game name
+            parent.spawn(
+                TextBundle::from_section(
+                    "Naughty n Nice",
+                    TextStyle {
+                        font_size: 35.0,
+                        color: TEXT_COLOR,
+                        ..default()
+                    },
+                )
+                .with_style(Style {
+                    margin: UiRect::all(Val::Px(10.0)),
+                    align_self: AlignSelf::End,
+                    ..default()
+                }),
+            );
+
+            // Display a button for each action available from the main menu:
+            // - new game, picking the number of players first
+            // - level select
+            // - high scores
+            // - options
+            // - quit
+            for (action, label, icon) in [
+                (MenuButtonAction::PlayModeSelect, "New Game", "right.png"),
+                (MenuButtonAction::LevelSelect, "Level select", "right.png"),
+                (MenuButtonAction::HighScores, "High scores", "right.png"),
+                (MenuButtonAction::Settings, "Options", "right.png"),
+                (MenuButtonAction::Quit, "Quit", "exitRight.png"),
+            ] {
+                spawn_button(parent, &button_style, action, label, &button_text_style)
+                    .with_children(|parent| {
+                        let icon = asset_server.load(format!("textures/Game Icons/{icon}"));
+                        parent.spawn(ImageBundle {
+                            style: button_icon_style.clone(),
+                            image: UiImage::new(icon),
+                            ..default()
+                        });
+                    });
+            }
+        },
+    );
+}
+
+// Display a button for each action showing the key and gamepad button bound to
+// it, pressing the button waits for the new one. Below them, buttons to reset
+// the bindings for QWERTY or AZERTY keyboards and to go back to the settings
+// screen.
+fn controls_setup(mut commands: Commands, asset_server: Res<AssetServer>) {
+    // Common style for all buttons on the screen
+    let button_style = Style {
+        width: Val::Percent(45.0),
+        height: Val::Percent(15.0),
+        margin: UiRect::all(Val::Px(2.0)),
+        justify_content: JustifyContent::Center,
+        align_items: AlignItems::Center,
+        ..default()
+    };
+    let button_text_style = TextStyle {
+        font_size: 16.0,
+        color: TEXT_COLOR,
+        ..default()
+    };
+    let layout = Style {
+        justify_content: JustifyContent::Center,
+        align_content: AlignContent::Center,
+        flex_wrap: FlexWrap::Wrap,
+        ..default()
+    };
+
+    let splash = spawn_splash(
+        &mut commands,
+        &asset_server,
+        OnControlsScreen,
+        layout,
+        |parent| {
+            // The binding of an action is filled in by `update_binding_labels`
+            for action in InputAction::ALL {
+                spawn_button(
+                    parent,
+                    &button_style,
+                    MenuButtonAction::Rebind(action),
+                    "",
+                    &button_text_style,
+                )
+                .insert(BindingLabel(action));
+            }
+            for (action, label) in [
+                (MenuButtonAction::QwertyBindings, "QWERTY"),
+                (MenuButtonAction::AzertyBindings, "AZERTY"),
+                (MenuButtonAction::BackToSettings, "Back"),
+            ] {
+                spawn_button(parent, &button_style, action, label, &button_text_style);
+            }
+        },
+    );
+    // Draw over the scoreboard when opened from the pause menu
+    commands.entity(splash).insert(ZIndex::Global(1));
+}
+
+// Bind the next key or gamepad button pressed to the action waiting for a new
+// binding, Esc cancels. A key or button that is the last one bound to another
+// action is refused, and the action keeps waiting
+fn capture_rebind(
+    keyboard_input: Res<Input<KeyCode>>,
+    gamepad_input: Res<Input<GamepadButton>>,
+    mut awaiting_rebind: ResMut<AwaitingRebind>,
+    mut input_map: ResMut<InputMap>,
+) {
+    let Some(action) = awaiting_rebind.0 else {
+        return;
+    };
+
+    if keyboard_input.just_pressed(KeyCode::Escape) {
+        awaiting_rebind.0 = None;
+        return;
+    }
+
+    let rebound = if let Some(key) = keyboard_input.get_just_pressed().next() {
+        input_map.rebind_key(action, *key)
+    } else if let Some(button) = gamepad_input.get_just_pressed().next() {
+        input_map.rebind_button(action, button.button_type)
+    } else {
+        false
+    };
+    if rebound {
+        awaiting_rebind.0 = None;
+    }
+}
+
+// Stop waiting for a new binding when leaving the controls screen
+fn cancel_rebind(mut awaiting_rebind: ResMut<AwaitingRebind>) {
+    awaiting_rebind.0 = None;
+}
+
+// Show the key and gamepad button bound to each action, or that a new binding
+// is expected
+fn update_binding_labels(
+    input_map: Res<InputMap>,
+    awaiting_rebind: Res<AwaitingRebind>,
+    button_query: Query<(&BindingLabel, &Children)>,
+    mut text_query: Query<&mut Text>,
+) {
+    for (label, children) in &button_query {
+        let binding = if awaiting_rebind.0 == Some(label.0) {
+            "press a key or button".to_string()
+        } else {
+            let key = input_map
+                .keys(label.0)
+                .first()
+                .map_or("-".to_string(), |key| format!("{key:?}"));
+            let button = input_map
+                .buttons(label.0)
+                .first()
+                .map_or("-".to_string(), |button| format!("{button:?}"));
+            format!("{key} / {button}")
+        };
+        let value = format!("{}: {}", label.0.label(), binding);
+
+        let mut text_iter = text_query.iter_many_mut(children.iter());
+        while let Some(mut text) = text_iter.fetch_next() {
+            if text.sections[0].value != value {
+                text.sections[0].value = value.clone();
+            }
+        }
+    }
+}
+
+// Display the table of high scores, with a button to go back to the main menu
+fn high_scores_setup(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    high_scores: Res<HighScores>,
+) {
+    let button_style = Style {
+        width: Val::Percent(50.0),
+        height: Val::Percent(13.0),
+        margin: UiRect::all(Val::Px(2.0)),
+        justify_content: JustifyContent::Center,
+        align_items: AlignItems::Center,
+        ..default()
+    };
+    let button_text_style = TextStyle {
+        font_size: 20.0,
+        color: TEXT_COLOR,
+        ..default()
+    };
+    let score_text_style = TextStyle {
+        font_size: 16.0,
+        color: STATS_TEXT_COLOR,
+        ..default()
+    };
+
+    spawn_splash(
+        &mut commands,
+        &asset_server,
+        OnHighScoresScreen,
+        centred_column(),
+        |parent| {
+            parent.spawn(TextBundle::from_section(
+                "High scores",
+                TextStyle {
+                    font_size: 25.0,
+                    color: TEXT_COLOR,
+                    ..default()
+                },
+            ));
+
+            // One line per score: rank, name, score and level
+            if high_scores.entries().is_empty() {
+                parent.spawn(TextBundle::from_section(
+                    "No scores yet",
+                    score_text_style.clone(),
+                ));
+            }
+            for (rank, high_score) in high_scores.entries().iter().enumerate() {
+                parent.spawn(TextBundle::from_section(
+                    format!(
+                        "{:>2}. {:<8} {:>6}  Level {}",
+                        rank + 1,
+                        high_score.name,
+                        high_score.score,
+                        high_score.level + 1
+                    ),
+                    score_text_style.clone(),
+                ));
+            }
+
+            spawn_button(
+                parent,
+                &button_style,
+                MenuButtonAction::BackToMainMenu,
+                "Back",
+                &button_text_style,
+            );
+        },
+    );
+}
+
+// Type the name of a new high score, which is recorded when Return is pressed
+// or the end of round screen is left
+fn type_high_score_name(
+    mut received_characters: EventReader<ReceivedCharacter>,
+    keyboard_input: Res<Input<KeyCode>>,
+    mut pending_high_score: ResMut<PendingHighScore>,
+    mut high_scores: ResMut<HighScores>,
+) {
+    let Some(high_score) = pending_high_score.0.as_mut() else {
+        received_characters.clear();
+        return;
+    };
+
+    for received in received_characters.iter() {
+        if received.char.is_ascii_alphanumeric() && high_score.name.len() < MAX_NAME_LENGTH {
+            high_score.name.push(received.char.to_ascii_uppercase());
+        }
+    }
+    if keyboard_input.just_pressed(KeyCode::Back) {
+        high_score.name.pop();
+    }
+    if keyboard_input.just_pressed(KeyCode::Return) {
+        pending_high_score.submit(&mut high_scores);
+    }
+}
+
+// Show the name being typed for a new high score, or that it has been saved
+fn update_high_score_name_label(
+    pending_high_score: Res<PendingHighScore>,
+    mut label_query: Query<&mut Text, With<HighScoreNameLabel>>,
+) {
+    for mut text in &mut label_query {
+        let value = match &pending_high_score.0 {
+            Some(high_score) => format!("New high score! Name: {}_", high_score.name),
+            None => "High score saved".to_string(),
+        };
+        if text.sections[0].value != value {
+            text.sections[0].value = value;
+        }
+    }
+}
+
+// Display a row for each setting, showing its value between buttons to lower
+// and raise it. Below them, buttons to open the controls screen and to go back
+// to the main menu (or the pause menu).
+fn settings_setup(mut commands: Commands, asset_server: Res<AssetServer>) {
+    // Common style for all buttons on the screen
+    let button_style = Style {
+        width: Val::Px(30.0),
+        height: Val::Px(30.0),
+        margin: UiRect::all(Val::Px(2.0)),
+        justify_content: JustifyContent::Center,
+        align_items: AlignItems::Center,
+        ..default()
+    };
+    let wide_button_style = Style {
+        width: Val::Percent(40.0),
+        height: Val::Px(30.0),
+        ..button_style.clone()
+    };
+    let label_style = Style {
+        width: Val::Px(200.0),
+        justify_content: JustifyContent::Center,
+        ..default()
+    };
+    let button_text_style = TextStyle {
+        font_size: 16.0,
+        color: TEXT_COLOR,
+        ..default()
+    };
+
+    let splash = spawn_splash(
+        &mut commands,
+        &asset_server,
+        OnSettingsScreen,
+        centred_column(),
+        |parent| {
+            for setting in [
+                Setting::MusicVolume,
+                Setting::SfxVolume,
+                Setting::MusicTrack,
+                Setting::WindowScale,
+            ] {
+                parent
+                    .spawn(NodeBundle {
+                        style: Style {
+                            align_items: AlignItems::Center,
+                            ..default()
+                        },
+                        ..default()
+                    })
+                    .with_children(|parent| {
+                        for (steps, label) in [(-1, "<"), (1, ">")] {
+                            spawn_button(
+                                parent,
+                                &button_style,
+                                MenuButtonAction::StepSetting(setting, steps),
+                                label,
+                                &button_text_style,
+                            );
+
+                            // The value of the setting, between the two buttons, is
+                            // filled in by `update_setting_labels`
+                            if steps < 0 {
+                                parent
+                                    .spawn(NodeBundle {
+                                        style: label_style.clone(),
+                                        ..default()
+                                    })
+                                    .with_children(|parent| {
+                                        parent.spawn((
+                                            TextBundle::from_section(
+                                                "",
+                                                TextStyle {
+                                                    color: STATS_TEXT_COLOR,
+                                                    ..button_text_style.clone()
+                                                },
+                                            ),
+                                            SettingLabel(setting),
+                                        ));
+                                    });
+                            }
+                        }
+                    });
+            }
+
+            for (action, label) in [
+                (MenuButtonAction::Controls, "Controls"),
+                (MenuButtonAction::BackToMainMenu, "Back"),
+            ] {
+                spawn_button(
+                    parent,
+                    &wide_button_style,
+                    action,
+                    label,
+                    &button_text_style,
+                );
+            }
+        },
+    );
+    // Draw over the scoreboard when opened from the pause menu
+    commands.entity(splash).insert(ZIndex::Global(1));
+}
+
+// Show the current value of each setting
+fn update_setting_labels(
+    settings: Res<Settings>,
+    mut label_query: Query<(&SettingLabel, &mut Text)>,
+) {
+    for (label, mut text) in &mut label_query {
+        let value = match label.0 {
+            Setting::MusicVolume => {
+                format!("Music: {:.0}%", settings.music_volume * 100.)
+            }
+            Setting::SfxVolume => format!("Effects: {:.0}%", settings.sfx_volume * 100.),
+            Setting::MusicTrack => format!("Game music: {}", settings.music_track.label()),
+            Setting::WindowScale => format!("Window: {}x", settings.window_scale),
+        };
+        if text.sections[0].value != value {
+            text.sections[0].value = value;
+        }
+    }
+}
+
+// Display the pause menu over the paused round, with buttons to resume or
+// restart the round, change the options or go back to the main menu
+fn pause_menu_setup(mut commands: Commands) {
+    // Common style for all buttons on the screen
+    let button_style = Style {
+        width: Val::Percent(50.0),
+        height: Val::Percent(16.0),
+        margin: UiRect::all(Val::Px(2.0)),
+        justify_content: JustifyContent::Center,
+        align_items: AlignItems::Center,
+        ..default()
+    };
+    let button_text_style = TextStyle {
+        font_size: 20.0,
+        color: TEXT_COLOR,
+        ..default()
+    };
+
     commands
         .spawn((
             NodeBundle {
                 style: Style {
-                    align_self: AlignSelf::Stretch,
-                    align_items: AlignItems::Start,
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    align_items: AlignItems::Center,
                     justify_content: JustifyContent::Center,
+                    flex_direction: FlexDirection::Column,
                     ..default()
                 },
+                background_color: PAUSE_OVERLAY.into(),
+                // Draw over the scoreboard of the round
+                z_index: ZIndex::Global(1),
                 ..default()
             },
-            OnMainMenuScreen,
+            OnPauseScreen,
         ))
         .with_children(|parent| {
-            // Display splash bitmap
-            let splash_image: Handle<Image> = asset_server.load("images/splash.png");
-            parent
-                .spawn(ImageBundle {
-                    style: Style {
-                        align_self: AlignSelf::End,
-                        width: Val::Px(480.),
-                        height: Val::Px(288.),
-                        justify_content: JustifyContent::SpaceAround,
-                        flex_direction: FlexDirection::Column,
-                        ..Default::default()
+            parent.spawn(
+                TextBundle::from_section(
+                    "Paused",
+                    TextStyle {
+                        font_size: 35.0,
+                        color: TEXT_COLOR,
+                        ..default()
                     },
-                    image: UiImage::new(splash_image),
+                )
+                .with_style(Style {
+                    margin: UiRect::all(Val::Px(10.0)),
                     ..default()
-                })
-                .with_children(|parent| {
-                    // Display the game name
-                    parent.spawn(
-                        TextBundle::from_section(
-                            "Naughty n Nice",
-                            TextStyle {
-                                font_size: 35.0,
-                                color: TEXT_COLOR,
-                                ..default()
-                            },
-                        )
-                        .with_style(Style {
-                            margin: UiRect::all(Val::Px(10.0)),
-                            align_self: AlignSelf::End,
-                            ..default()
-                        }),
-                    );
+                }),
+            );
+
+            // Display a button for each action available from the pause menu:
+            // - resume
+            // - restart the round
+            // - options
+            // - back to the main menu
+            for (action, label) in [
+                (MenuButtonAction::Resume, "Resume"),
+                (MenuButtonAction::Restart, "Restart"),
+                (MenuButtonAction::Settings, "Options"),
+                (MenuButtonAction::MainMenu, "Quit to menu"),
+            ] {
+                spawn_button(parent, &button_style, action, label, &button_text_style);
+            }
+        });
+}
+
+// Display a button for each play mode, starting a new campaign in that mode,
+// and a button to go back to the main menu
+fn play_mode_select_setup(mut commands: Commands, asset_server: Res<AssetServer>) {
+    // Common style for all buttons on the screen
+    let button_style = Style {
+        width: Val::Percent(50.0),
+        height: Val::Percent(16.0),
+        margin: UiRect::all(Val::Px(2.0)),
+        justify_content: JustifyContent::Center,
+        align_items: AlignItems::Center,
+        align_self: AlignSelf::End,
+        ..default()
+    };
+    let button_text_style = TextStyle {
+        font_size: 20.0,
+        color: TEXT_COLOR,
+        ..default()
+    };
 
-                    // Display two buttons for each action available from the main menu:
-                    // - new game
-                    // - quit
+    spawn_splash(
+        &mut commands,
+        &asset_server,
+        OnPlayModeSelectScreen,
+        spread_column(),
+        |parent| {
+            let modes = PlayMode::ALL.map(|mode| (MenuButtonAction::NewGameIn(mode), mode.label()));
+
+            for (action, label) in modes
+                .into_iter()
+                .chain([(MenuButtonAction::BackToMainMenu, "Back")])
+            {
+                spawn_button(parent, &button_style, action, label, &button_text_style);
+            }
+        },
+    );
+}
+
+// Display a button for each level of the campaign, only the unlocked ones
+// can be played, and a button to go back to the main menu
+fn level_select_setup(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    campaign: Res<Campaign>,
+) {
+    // Common style for all buttons on the screen
+    let button_style = Style {
+        width: Val::Percent(50.0),
+        height: Val::Percent(16.0),
+        margin: UiRect::all(Val::Px(2.0)),
+        justify_content: JustifyContent::Center,
+        align_items: AlignItems::Center,
+        align_self: AlignSelf::End,
+        ..default()
+    };
+    let button_text_style = TextStyle {
+        font_size: 20.0,
+        color: TEXT_COLOR,
+        ..default()
+    };
+
+    spawn_splash(
+        &mut commands,
+        &asset_server,
+        OnLevelSelectScreen,
+        spread_column(),
+        |parent| {
+            for level in 0..LEVEL_COUNT {
+                let label = format!("Level {}", level + 1);
+                if campaign.is_unlocked(level) {
+                    spawn_button(
+                        parent,
+                        &button_style,
+                        MenuButtonAction::PlayLevel(level),
+                        label,
+                        &button_text_style,
+                    );
+                } else {
+                    // Locked levels are shown greyed out and can't be pressed
                     parent
-                        .spawn((
-                            ButtonBundle {
-                                style: button_style.clone(),
-                                background_color: NORMAL_BUTTON.into(),
-                                ..default()
-                            },
-                            MenuButtonAction::Play,
-                        ))
+                        .spawn(NodeBundle {
+                            style: button_style.clone(),
+                            background_color: LOCKED_BUTTON.into(),
+                            ..default()
+                        })
                         .with_children(|parent| {
-                            let icon = asset_server.load("textures/Game Icons/right.png");
-                            parent.spawn(ImageBundle {
-                                style: button_icon_style.clone(),
-                                image: UiImage::new(icon),
-                                ..default()
-                            });
                             parent.spawn(TextBundle::from_section(
-                                "New Game",
-                                button_text_style.clone(),
+                                label,
+                                TextStyle {
+                                    color: LOCKED_TEXT_COLOR,
+                                    ..button_text_style.clone()
+                                },
                             ));
                         });
-                    parent
-                        .spawn((
-                            ButtonBundle {
-                                style: button_style,
-                                background_color: NORMAL_BUTTON.into(),
-                                ..default()
-                            },
-                            MenuButtonAction::Quit,
-                        ))
-                        .with_children(|parent| {
-                            let icon = asset_server.load("textures/Game Icons/exitRight.png");
-                            parent.spawn(ImageBundle {
-                                style: button_icon_style,
-                                image: UiImage::new(icon),
-                                ..default()
-                            });
-                            parent.spawn(TextBundle::from_section("Quit", button_text_style));
-                        });
-                });
-        });
+                }
+            }
+            spawn_button(
+                parent,
+                &button_style,
+                MenuButtonAction::BackToMainMenu,
+                "Back",
+                &button_text_style,
+            );
+        },
+    );
+}
+
+// In a versus game, the end of round screens announce the winner
+fn winner_text(play_mode: PlayMode, round_result: &RoundResult, text: &str) -> String {
+    if play_mode == PlayMode::Versus {
+        format!("Player {} wins!", round_result.player + 1)
+    } else {
+        text.to_string()
+    }
+}
+
+fn level_complete_screen_setup(
+    commands: Commands,
+    asset_server: Res<AssetServer>,
+    round_result: Res<RoundResult>,
+    pending_high_score: Res<PendingHighScore>,
+    play_mode: Res<PlayMode>,
+) {
+    end_screen_setup(
+        commands,
+        asset_server,
+        &round_result,
+        pending_high_score.0.is_some(),
+        (
+            &winner_text(*play_mode, &round_result, "Level complete!"),
+            WIN_TEXT_COLOR,
+        ),
+        WIN_SPLASH_TINT,
+        (MenuButtonAction::Play, "Next level"),
+    );
+}
+
+fn win_screen_setup(
+    commands: Commands,
+    asset_server: Res<AssetServer>,
+    round_result: Res<RoundResult>,
+    pending_high_score: Res<PendingHighScore>,
+    play_mode: Res<PlayMode>,
+) {
+    end_screen_setup(
+        commands,
+        asset_server,
+        &round_result,
+        pending_high_score.0.is_some(),
+        (
+            &winner_text(*play_mode, &round_result, "Campaign complete!"),
+            WIN_TEXT_COLOR,
+        ),
+        WIN_SPLASH_TINT,
+        (MenuButtonAction::NewGame, "Play again"),
+    );
+}
+
+fn lose_screen_setup(
+    commands: Commands,
+    asset_server: Res<AssetServer>,
+    round_result: Res<RoundResult>,
+) {
+    end_screen_setup(
+        commands,
+        asset_server,
+        &round_result,
+        false,
+        ("Oh no! You lost!", LOSE_TEXT_COLOR),
+        LOSE_SPLASH_TINT,
+        (MenuButtonAction::Play, "Play again"),
+    );
+}
+
+// Display the outcome and final statistics of the round over the (tinted)
+// splash bitmap, with buttons to start the next round, go back to the main
+// menu or quit. A new high score asks for the player's name above the buttons.
+fn end_screen_setup(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    round_result: &RoundResult,
+    new_high_score: bool,
+    (text, color): (&str, Color),
+    splash_tint: Color,
+    next_round: (MenuButtonAction, &str),
+) {
+    // Common style for all buttons on the screen
+    let button_style = Style {
+        width: Val::Percent(50.0),
+        height: Val::Percent(18.0),
+        margin: UiRect::all(Val::Px(2.0)),
+        justify_content: JustifyContent::Center,
+        align_items: AlignItems::Center,
+        align_self: AlignSelf::End,
+        ..default()
+    };
+    let button_text_style = TextStyle {
+        font_size: 20.0,
+        color: TEXT_COLOR,
+        ..default()
+    };
+
+    let splash = spawn_splash(
+        &mut commands,
+        &asset_server,
+        OnEndScreen,
+        spread_column(),
+        |parent| {
+            // Display the outcome of the round
+            parent.spawn(
+                TextBundle::from_section(
+                    text,
+                    TextStyle {
+                        font_size: 35.0,
+                        color,
+                        ..default()
+                    },
+                )
+                .with_style(Style {
+                    margin: UiRect::all(Val::Px(10.0)),
+                    align_self: AlignSelf::End,
+                    ..default()
+                }),
+            );
+
+            // Display the number of presents collected, the health left
+            // and the score
+            parent.spawn(
+                TextBundle::from_section(
+                    format!(
+                        "Presents: {}   Health: {}   Score: {}",
+                        round_result.presents, round_result.health, round_result.score
+                    ),
+                    TextStyle {
+                        font_size: 20.0,
+                        color: STATS_TEXT_COLOR,
+                        ..default()
+                    },
+                )
+                .with_style(Style {
+                    margin: UiRect::horizontal(Val::Px(10.0)),
+                    align_self: AlignSelf::End,
+                    ..default()
+                }),
+            );
+
+            // The name typed is filled in by `update_high_score_name_label`
+            if new_high_score {
+                parent.spawn((
+                    TextBundle::from_section(
+                        "",
+                        TextStyle {
+                            font_size: 20.0,
+                            color: WIN_TEXT_COLOR,
+                            ..default()
+                        },
+                    )
+                    .with_style(Style {
+                        margin: UiRect::horizontal(Val::Px(10.0)),
+                        align_self: AlignSelf::End,
+                        ..default()
+                    }),
+                    HighScoreNameLabel,
+                ));
+            }
+
+            // Display a button for each action available at the end of a round:
+            // - play again or next level
+            // - back to the main menu
+            // - quit
+            for (action, label) in [
+                next_round,
+                (MenuButtonAction::MainMenu, "Main menu"),
+                (MenuButtonAction::Quit, "Quit"),
+            ] {
+                spawn_button(parent, &button_style, action, label, &button_text_style);
+            }
+        },
+    );
+    commands.entity(splash).insert(BackgroundColor(splash_tint));
 }
@@ -1,15 +1,108 @@
-use bevy::ecs::component::Component;
+use bevy::{
+    app::{Plugin, Update},
+    ecs::{
+        component::Component,
+        query::{With, Without},
+        schedule::{common_conditions::in_state, Condition, IntoSystemConfigs},
+        system::{Query, Res},
+    },
+    math::Vec2,
+    time::Time,
+    transform::components::{GlobalTransform, Transform},
+};
 
-use crate::characters::Direction;
+use crate::{
+    characters::{CharacterState, Direction, Status},
+    markers::CharacterMarker,
+    pause::PauseState,
+    setup::{
+        CHARACTER_BOTTOM_BOUND, CHARACTER_LEFT_BOUND, CHARACTER_RIGHT_BOUND, CHARACTER_TOP_BOUND,
+    },
+    tile_collision::{collider_rect, move_and_slide, Collider},
+    GameState,
+};
 
+/// Movement speed in pixels per second.
 #[derive(Clone)]
 pub(crate) struct Speed(pub f32);
 
 /// Component used to store a moveable entity's current direction
 /// and speed.
-#[allow(unused)]
 #[derive(Component)]
 pub(crate) struct Moveable {
-    pub(crate) direction: Direction,
+    /// Movement direction, no longer than 1. Zero when standing still.
+    direction: Vec2,
     pub(crate) speed: Speed,
 }
+
+impl Moveable {
+    pub(crate) fn new(speed: Speed) -> Self {
+        Self {
+            direction: Vec2::ZERO,
+            speed,
+        }
+    }
+
+    /// Returns the current movement direction.
+    pub(crate) fn direction(&self) -> Vec2 {
+        self.direction
+    }
+
+    /// Set the movement direction. Vectors longer than 1, such as two keys
+    /// pressed for a diagonal, are normalised, shorter ones (from an analog
+    /// stick) are kept so the character moves slower.
+    pub(crate) fn set_direction(&mut self, direction: Vec2) {
+        self.direction = direction.clamp_length_max(1.);
+    }
+
+    /// Returns the direction the sprite is facing, along the dominant axis of
+    /// the movement.
+    pub(crate) fn facing(&self) -> Direction {
+        Direction::from_vector(self.direction)
+    }
+}
+
+/// Defines a plugin used to move characters every frame based on their
+/// [`Moveable`] direction and speed.
+pub(crate) struct Movement;
+
+impl Plugin for Movement {
+    fn build(&self, app: &mut bevy::prelude::App) {
+        app.add_systems(
+            Update,
+            move_characters
+                .run_if(in_state(GameState::Game).and_then(in_state(PauseState::Running))),
+        );
+    }
+}
+
+/// Move the characters that are still `Alive` in their current direction,
+/// scaled by their speed and the time since the last frame. Movement is
+/// blocked by the tiles with a [`Collider`] and the edges of the level.
+pub(crate) fn move_characters(
+    time: Res<Time>,
+    mut query: Query<(&mut Transform, &Moveable, &Status), With<CharacterMarker>>,
+    collider_query: Query<&GlobalTransform, (With<Collider>, Without<CharacterMarker>)>,
+) {
+    let colliders = collider_query.iter().map(collider_rect).collect::<Vec<_>>();
+
+    for (mut transform, moveable, status) in &mut query {
+        if status.state() != CharacterState::Alive {
+            continue;
+        }
+
+        let direction = moveable.direction();
+        if direction == Vec2::ZERO {
+            continue;
+        }
+        let delta = direction * moveable.speed.0 * time.delta_seconds();
+
+        let new_position = move_and_slide(transform.translation, delta, &colliders);
+        transform.translation.x = new_position
+            .x
+            .clamp(CHARACTER_LEFT_BOUND, CHARACTER_RIGHT_BOUND);
+        transform.translation.y = new_position
+            .y
+            .clamp(CHARACTER_BOTTOM_BOUND, CHARACTER_TOP_BOUND);
+    }
+}
@@ -0,0 +1,85 @@
+use bevy::{
+    app::{Plugin, Update},
+    ecs::{
+        schedule::{
+            common_conditions::in_state, Condition, IntoSystemConfigs, NextState, OnEnter, OnExit,
+            States,
+        },
+        system::{Res, ResMut},
+    },
+    input::{gamepad::GamepadButton, keyboard::KeyCode, Input},
+    time::Time,
+};
+
+use crate::{
+    control_input::{ActiveGamepad, InputAction, InputMap},
+    GameState,
+};
+
+/// Whether the round being played is paused. Only meaningful while in
+/// [`GameState::Game`], it is reset to `Running` when the round ends.
+#[derive(Clone, Copy, Default, Eq, PartialEq, Debug, Hash, States)]
+pub(crate) enum PauseState {
+    #[default]
+    Running,
+    Paused,
+}
+
+/// Plugin pausing the round with the key or gamepad button bound to
+/// [`InputAction::Pause`]. Gameplay systems only run in
+/// [`PauseState::Running`], and [`Time`] is frozen while paused so that timers
+/// carry on where they left off when the round resumes. The pause menu itself
+/// is displayed by [`crate::menu::MenuPlugin`].
+pub(crate) struct GamePause;
+
+impl Plugin for GamePause {
+    fn build(&self, app: &mut bevy::prelude::App) {
+        app.add_state::<PauseState>()
+            .add_systems(OnEnter(PauseState::Paused), freeze_time)
+            .add_systems(OnExit(PauseState::Paused), unfreeze_time)
+            .add_systems(OnExit(GameState::Game), resume)
+            .add_systems(OnEnter(GameState::Restarting), restart_round)
+            .add_systems(
+                Update,
+                pause.run_if(in_state(GameState::Game).and_then(in_state(PauseState::Running))),
+            );
+    }
+}
+
+/// Pause the round with the key or gamepad button bound to
+/// [`InputAction::Pause`].
+fn pause(
+    keyboard_input: Res<Input<KeyCode>>,
+    gamepad_input: Res<Input<GamepadButton>>,
+    active_gamepad: Res<ActiveGamepad>,
+    input_map: Res<InputMap>,
+    mut pause_state: ResMut<NextState<PauseState>>,
+) {
+    let gamepad_pressed = active_gamepad.0.is_some_and(|gamepad| {
+        input_map.button_just_pressed(InputAction::Pause, gamepad, &gamepad_input)
+    });
+
+    if gamepad_pressed || input_map.just_pressed(InputAction::Pause, &keyboard_input) {
+        pause_state.set(PauseState::Paused);
+    }
+}
+
+fn freeze_time(mut time: ResMut<Time>) {
+    time.pause();
+}
+
+fn unfreeze_time(mut time: ResMut<Time>) {
+    time.unpause();
+}
+
+/// A round left while paused, to restart it or go back to the main menu, is
+/// no longer paused.
+fn resume(mut pause_state: ResMut<NextState<PauseState>>) {
+    pause_state.set(PauseState::Running);
+}
+
+/// Go straight back into [`GameState::Game`], so that the round is despawned
+/// and spawned again from the start.
+fn restart_round(mut game_state: ResMut<NextState<GameState>>) {
+    game_state.set(GameState::Game);
+}
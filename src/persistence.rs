@@ -0,0 +1,209 @@
+use std::{fs, path::PathBuf};
+
+use bevy::{
+    app::{App, Update},
+    ecs::{
+        change_detection::DetectChanges,
+        system::{Res, Resource},
+    },
+    log::warn,
+};
+use directories::ProjectDirs;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+/// A resource saved to a RON file, loaded when the app is built and written
+/// back whenever it changes. See [`PersistAppExt::init_persisted_resource`].
+pub(crate) trait Persisted: Resource + Serialize + DeserializeOwned + Default {
+    /// Name of the file, in the directory given by [`Persisted::LOCATION`].
+    const FILE: &'static str;
+    const LOCATION: Location;
+    /// Version of the file format. Bump it whenever a change means older
+    /// files can no longer be read, those are then replaced by the defaults.
+    const VERSION: u32;
+
+    /// Bring values read from a file that may have been edited by hand back
+    /// within their allowed ranges.
+    fn sanitized(self) -> Self {
+        self
+    }
+}
+
+/// The platform directory a [`Persisted`] resource is saved in.
+#[derive(Clone, Copy)]
+pub(crate) enum Location {
+    /// Settings chosen by the player, such as the key bindings.
+    Config,
+    /// Records of play, such as the campaign progress.
+    Data,
+}
+
+impl Location {
+    /// Returns the path of the given file in this directory, if the platform
+    /// has one.
+    fn path(&self, file: &str) -> Option<PathBuf> {
+        let dirs = ProjectDirs::from("", "", "naughty_n_nice")?;
+        let dir = match self {
+            Location::Config => dirs.config_dir(),
+            Location::Data => dirs.data_dir(),
+        };
+        Some(dir.join(file))
+    }
+}
+
+/// Record of the player's progress across runs of the game.
+#[derive(Resource, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub(crate) struct Progress {
+    /// Number of campaign levels that can be picked from the level select
+    /// screen.
+    pub(crate) unlocked_levels: usize,
+    /// Number of levels won, including those won more than once.
+    pub(crate) levels_won: u32,
+    /// Number of times the last level of the campaign has been won.
+    pub(crate) campaigns_won: u32,
+    /// Most nice presents collected over a whole campaign.
+    pub(crate) best_campaign_presents: u8,
+}
+
+impl Default for Progress {
+    fn default() -> Self {
+        Self {
+            unlocked_levels: 1,
+            levels_won: 0,
+            campaigns_won: 0,
+            best_campaign_presents: 0,
+        }
+    }
+}
+
+impl Persisted for Progress {
+    const FILE: &'static str = "progress.ron";
+    const LOCATION: Location = Location::Data;
+    const VERSION: u32 = 1;
+}
+
+/// The contents of a file, tagged with the version of its format.
+#[derive(Serialize, Deserialize)]
+struct Versioned<T> {
+    version: u32,
+    data: T,
+}
+
+/// Only the version of a file, read before the rest so that files in an
+/// older format aren't mistaken for corrupted ones.
+#[derive(Deserialize)]
+struct VersionHeader {
+    version: u32,
+}
+
+/// Load the resource from its file, falling back to the defaults if there is
+/// none. An unreadable file, corrupted or in another version of the format,
+/// is moved aside so it isn't overwritten by the next save.
+fn load<T: Persisted>() -> T {
+    let Some(path) = T::LOCATION.path(T::FILE) else {
+        return T::default();
+    };
+    let Ok(contents) = fs::read_to_string(&path) else {
+        return T::default();
+    };
+
+    match parse::<T>(&contents) {
+        Ok(value) => value,
+        Err(error) => {
+            warn!("Unable to read {}, using defaults: {error}", path.display());
+            if let Err(error) = fs::rename(&path, path.with_extension("ron.bak")) {
+                warn!("Unable to back up {}: {error}", path.display());
+            }
+            T::default()
+        }
+    }
+}
+
+/// Read the resource from the contents of its file, checking the version of
+/// the format first and sanitizing the values read.
+fn parse<T: Persisted>(contents: &str) -> Result<T, String> {
+    let header = ron::from_str::<VersionHeader>(contents).map_err(|error| error.to_string())?;
+    if header.version != T::VERSION {
+        return Err(format!(
+            "version {} is not supported, expected {}",
+            header.version,
+            T::VERSION
+        ));
+    }
+
+    ron::from_str::<Versioned<T>>(contents)
+        .map(|versioned| versioned.data.sanitized())
+        .map_err(|error| error.to_string())
+}
+
+/// Save the resource to its file, creating the directory if needed.
+fn save<T: Persisted>(value: &T) -> Result<(), String> {
+    let path = T::LOCATION.path(T::FILE).ok_or("no directory to save to")?;
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(|error| error.to_string())?;
+    }
+
+    let versioned = Versioned {
+        version: T::VERSION,
+        data: value,
+    };
+    let contents = ron::ser::to_string_pretty(&versioned, ron::ser::PrettyConfig::default())
+        .map_err(|error| error.to_string())?;
+    fs::write(path, contents).map_err(|error| error.to_string())
+}
+
+/// Save the resource whenever it is changed.
+fn save_on_change<T: Persisted>(value: Res<T>) {
+    if !value.is_changed() || value.is_added() {
+        return;
+    }
+
+    if let Err(error) = save(&*value) {
+        warn!("Unable to save {}: {error}", T::FILE);
+    }
+}
+
+/// Adds [`Persisted`] resources to an [`App`].
+pub(crate) trait PersistAppExt {
+    /// Insert the resource loaded from its file, and save it whenever it is
+    /// changed.
+    fn init_persisted_resource<T: Persisted>(&mut self) -> &mut Self;
+}
+
+impl PersistAppExt for App {
+    fn init_persisted_resource<T: Persisted>(&mut self) -> &mut Self {
+        self.insert_resource(load::<T>())
+            .add_systems(Update, save_on_change::<T>)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_current_version() {
+        let progress =
+            parse::<Progress>("(version: 1, data: (unlocked_levels: 3, levels_won: 4))").unwrap();
+
+        assert_eq!(progress.unlocked_levels, 3);
+        assert_eq!(progress.levels_won, 4);
+        assert_eq!(progress.campaigns_won, 0);
+    }
+
+    #[test]
+    fn parse_corrupted_file() {
+        assert!(parse::<Progress>("(version: 1, data: (unlocked_levels: ").is_err());
+        assert!(parse::<Progress>("").is_err());
+    }
+
+    #[test]
+    fn parse_old_version() {
+        let result = parse::<Progress>("(version: 0, data: (unlocked_levels: 3))");
+
+        assert_eq!(
+            result.err().as_deref(),
+            Some("version 0 is not supported, expected 1")
+        );
+    }
+}
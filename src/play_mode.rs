@@ -0,0 +1,41 @@
+use bevy::ecs::system::Resource;
+
+/// How many players share the round and how they win it, picked from the
+/// main menu.
+#[derive(Resource, Clone, Copy, Default, PartialEq, Eq, Debug)]
+pub(crate) enum PlayMode {
+    #[default]
+    SinglePlayer,
+    /// Two players collecting nice presents towards the win target of the
+    /// level together.
+    Cooperative,
+    /// Two players racing to be the first to collect the win target of the
+    /// level.
+    Versus,
+}
+
+impl PlayMode {
+    /// All modes, in the order they are listed on the menu.
+    pub(crate) const ALL: [PlayMode; 3] = [
+        PlayMode::SinglePlayer,
+        PlayMode::Cooperative,
+        PlayMode::Versus,
+    ];
+
+    /// Returns the number of players in the round.
+    pub(crate) fn player_count(&self) -> usize {
+        match self {
+            PlayMode::SinglePlayer => 1,
+            PlayMode::Cooperative | PlayMode::Versus => 2,
+        }
+    }
+
+    /// Name of the mode shown to the player.
+    pub(crate) fn label(&self) -> &'static str {
+        match self {
+            PlayMode::SinglePlayer => "1 Player",
+            PlayMode::Cooperative => "2 Players: co-op",
+            PlayMode::Versus => "2 Players: versus",
+        }
+    }
+}
@@ -1,16 +1,51 @@
 use bevy::ecs::component::Component;
+use serde::Deserialize;
 
-#[allow(unused)]
-#[derive(PartialEq, Eq, Clone, Copy)]
+mod behaviour;
+
+pub(crate) use behaviour::{MovingPresents, PresentBehaviour};
+
+#[derive(PartialEq, Eq, Clone, Copy, Deserialize)]
 pub(crate) enum PresentType {
-    // Naughty stores the number of hit points to remove
+    /// Removes the given number of hit points.
     Naughty(u8),
+    /// Counts towards the win target.
     Nice,
+    /// Restores the given number of hit points, up to the health the player
+    /// started the level with.
+    Healing(u8),
+    /// Makes the player walk faster for a while.
+    SpeedBoost,
+    /// Absorbs the next hit from a naughty present or an enemy within a
+    /// while.
+    Shield,
+    /// A nice present that also makes nice presents worth double for a
+    /// while.
+    Golden,
+}
+
+impl PresentType {
+    /// Returns the path of the sprite used to draw a present of this type.
+    pub(crate) fn sprite_path(&self) -> &'static str {
+        match self {
+            PresentType::Naughty(_) => "sprites/Gifts_Red.png",
+            PresentType::Nice => "sprites/Gifts_Green.png",
+            PresentType::Healing(_) => "sprites/Gifts_Pink.png",
+            PresentType::SpeedBoost => "sprites/Gifts_Blue.png",
+            PresentType::Shield => "sprites/Gifts_Silver.png",
+            PresentType::Golden => "sprites/Gifts_Gold.png",
+        }
+    }
 }
 
 /// Component that defines a present of a given type:
-/// - Naughty: this will remove health points
+/// - Naughty: this will remove health points, unless shielded
 /// - Nice: this will count towards the collected present total
+/// - Healing: this will restore health points
+/// - SpeedBoost: the player walks faster for a while
+/// - Shield: the next naughty present is absorbed, if within a while
+/// - Golden: this will count towards the collected present total, and nice
+///   presents are worth more points for a while
 #[derive(Component)]
 pub(crate) struct Present {
     present_type: PresentType,
@@ -0,0 +1,271 @@
+use bevy::{
+    app::{Plugin, Update},
+    asset::Assets,
+    ecs::{
+        component::Component,
+        entity::Entity,
+        query::{Added, With},
+        schedule::{common_conditions::in_state, IntoSystemConfigs},
+        system::{Commands, Query, Res},
+    },
+    math::{Rect, Vec2, Vec3},
+    time::{Time, Timer, TimerMode},
+    transform::components::{GlobalTransform, Transform},
+};
+use rand::Rng;
+use serde::Deserialize;
+
+use super::Present;
+use crate::{
+    ai::nearest_player_in_sight,
+    characters::{Player, Status},
+    level_config::{LevelConfig, LevelConfigHandle},
+    pause::PauseState,
+    setup::{
+        CHARACTER_BOTTOM_BOUND, CHARACTER_LEFT_BOUND, CHARACTER_RIGHT_BOUND, CHARACTER_TOP_BOUND,
+    },
+    tile_collision::{collider_rect, slide_rect, Collider},
+    GameState,
+};
+
+/// Size of the box around a present that is tested against the colliders,
+/// smaller than its sprite so that it fits through gaps one tile wide.
+const PRESENT_COLLISION_SIZE: Vec2 = Vec2::new(32., 32.);
+
+/// How fast a hopping present moves during a hop, in pixels per second, and
+/// for how long.
+const HOP_SPEED: f32 = 240.;
+const HOP_SECONDS: f32 = 0.25;
+
+/// Time a present rests on the ground after a hop before it can hop again.
+const HOP_REST_SECONDS: f32 = 0.5;
+
+/// How fast a drifting present moves, in pixels per second. Slower than the
+/// player so that it can be outrun.
+const DRIFT_SPEED: f32 = 40.;
+
+/// Number of random locations tried when teleporting a present, before
+/// giving up until the next timeout.
+const TELEPORT_ATTEMPTS: usize = 10;
+
+/// How a present moves around the level, on top of waiting to be picked up.
+#[derive(Clone, Copy, Deserialize)]
+pub(crate) enum PresentBehaviour {
+    /// Hop away from a player coming within the given distance in pixels.
+    Hop(f32),
+    /// Drift towards the nearest player within the given distance in pixels.
+    Drift(f32),
+    /// Teleport to a random location every given number of seconds.
+    Teleport(f32),
+}
+
+/// A present hopping away from the players, see [`PresentBehaviour::Hop`].
+#[derive(Component)]
+struct Hop {
+    sight_radius: f32,
+    state: HopState,
+}
+
+enum HopState {
+    /// Waiting for a player to come near.
+    Ready,
+    /// Moving in the direction for the duration of the hop.
+    Hopping(Vec2, Timer),
+    /// Back on the ground until the timer finishes.
+    Resting(Timer),
+}
+
+/// A present drifting towards the players, see [`PresentBehaviour::Drift`].
+#[derive(Component)]
+struct Drift {
+    sight_radius: f32,
+}
+
+/// A present teleporting when the timer finishes, see
+/// [`PresentBehaviour::Teleport`].
+#[derive(Component)]
+struct Teleport(Timer);
+
+/// Plugin giving the presents, whether placed in the LDtk level or spawned,
+/// the [`PresentBehaviour`]s listed for their type in the [`LevelConfig`],
+/// and moving them accordingly.
+pub(crate) struct MovingPresents;
+
+impl Plugin for MovingPresents {
+    fn build(&self, app: &mut bevy::prelude::App) {
+        app.add_systems(
+            Update,
+            (
+                setup_present_behaviours,
+                (hop, drift, teleport).run_if(in_state(PauseState::Running)),
+            )
+                .run_if(in_state(GameState::Game)),
+        );
+    }
+}
+
+/// Add the behaviours listed in the [`LevelConfig`] for their type to the new
+/// presents.
+fn setup_present_behaviours(
+    mut commands: Commands,
+    level_config: Res<LevelConfigHandle>,
+    level_configs: Res<Assets<LevelConfig>>,
+    query: Query<(Entity, &Present), Added<Present>>,
+) {
+    if query.is_empty() {
+        return;
+    }
+
+    let level_config = level_config.get(&level_configs);
+
+    for (entity, present) in &query {
+        let mut entity = commands.entity(entity);
+        for behaviour in level_config
+            .present_behaviours
+            .for_type(present.present_type())
+        {
+            match *behaviour {
+                PresentBehaviour::Hop(sight_radius) => entity.insert(Hop {
+                    sight_radius,
+                    state: HopState::Ready,
+                }),
+                PresentBehaviour::Drift(sight_radius) => entity.insert(Drift { sight_radius }),
+                PresentBehaviour::Teleport(seconds) => {
+                    entity.insert(Teleport(Timer::from_seconds(seconds, TimerMode::Repeating)))
+                }
+            };
+        }
+    }
+}
+
+/// Returns the position of the players with their [`Status`], for
+/// [`nearest_player_in_sight`].
+fn player_positions<'a>(
+    player_query: &'a Query<(&GlobalTransform, &Status), With<Player>>,
+) -> Vec<(Vec2, &'a Status)> {
+    player_query
+        .iter()
+        .map(|(transform, status)| (transform.translation().truncate(), status))
+        .collect()
+}
+
+/// Move a present by `delta` in the world, stopping against the colliders
+/// and the edges of the level. Presents placed in the LDtk level are
+/// positioned relative to the level, so the move is applied to `transform`
+/// as an offset from the position in the world.
+fn slide_present(
+    transform: &mut Transform,
+    global_transform: &GlobalTransform,
+    delta: Vec2,
+    colliders: &[Rect],
+) {
+    let position = global_transform.translation();
+    let rect = Rect::from_center_size(position.truncate(), PRESENT_COLLISION_SIZE);
+    let moved = slide_rect(rect, delta, colliders).center();
+    let moved = Vec3::new(
+        moved.x.clamp(CHARACTER_LEFT_BOUND, CHARACTER_RIGHT_BOUND),
+        moved.y.clamp(CHARACTER_BOTTOM_BOUND, CHARACTER_TOP_BOUND),
+        position.z,
+    );
+    transform.translation += moved - position;
+}
+
+/// Hop the presents away from the nearest player in sight, resting between
+/// hops.
+fn hop(
+    time: Res<Time>,
+    mut query: Query<(&mut Transform, &GlobalTransform, &mut Hop)>,
+    player_query: Query<(&GlobalTransform, &Status), With<Player>>,
+    collider_query: Query<&GlobalTransform, With<Collider>>,
+) {
+    let colliders = collider_query.iter().map(collider_rect).collect::<Vec<_>>();
+    let players = player_positions(&player_query);
+
+    for (mut transform, global_transform, mut hop) in &mut query {
+        let sight_radius = hop.sight_radius;
+        match &mut hop.state {
+            HopState::Ready => {
+                let position = global_transform.translation().truncate();
+                if let Some(player) =
+                    nearest_player_in_sight(position, sight_radius, players.iter().copied())
+                {
+                    let away = (position - player).try_normalize().unwrap_or(Vec2::Y);
+                    hop.state =
+                        HopState::Hopping(away, Timer::from_seconds(HOP_SECONDS, TimerMode::Once));
+                }
+            }
+            HopState::Hopping(direction, timer) => {
+                let delta = *direction * HOP_SPEED * time.delta_seconds();
+                slide_present(&mut transform, global_transform, delta, &colliders);
+
+                if timer.tick(time.delta()).finished() {
+                    hop.state =
+                        HopState::Resting(Timer::from_seconds(HOP_REST_SECONDS, TimerMode::Once));
+                }
+            }
+            HopState::Resting(timer) => {
+                if timer.tick(time.delta()).finished() {
+                    hop.state = HopState::Ready;
+                }
+            }
+        }
+    }
+}
+
+/// Drift the presents towards the nearest player in sight.
+fn drift(
+    time: Res<Time>,
+    mut query: Query<(&mut Transform, &GlobalTransform, &Drift)>,
+    player_query: Query<(&GlobalTransform, &Status), With<Player>>,
+    collider_query: Query<&GlobalTransform, With<Collider>>,
+) {
+    let colliders = collider_query.iter().map(collider_rect).collect::<Vec<_>>();
+    let players = player_positions(&player_query);
+
+    for (mut transform, global_transform, drift) in &mut query {
+        let position = global_transform.translation().truncate();
+        let Some(player) =
+            nearest_player_in_sight(position, drift.sight_radius, players.iter().copied())
+        else {
+            continue;
+        };
+
+        let delta = (player - position).normalize_or_zero() * DRIFT_SPEED * time.delta_seconds();
+        slide_present(&mut transform, global_transform, delta, &colliders);
+    }
+}
+
+/// Move the presents whose timer has finished to a random location clear of
+/// the colliders.
+fn teleport(
+    time: Res<Time>,
+    mut query: Query<(&mut Transform, &GlobalTransform, &mut Teleport)>,
+    collider_query: Query<&GlobalTransform, With<Collider>>,
+) {
+    let colliders = collider_query.iter().map(collider_rect).collect::<Vec<_>>();
+    let mut rng = rand::thread_rng();
+
+    for (mut transform, global_transform, mut teleport) in &mut query {
+        if !teleport.0.tick(time.delta()).just_finished() {
+            continue;
+        }
+
+        let destination = (0..TELEPORT_ATTEMPTS)
+            .map(|_| {
+                Vec2::new(
+                    rng.gen_range(CHARACTER_LEFT_BOUND..CHARACTER_RIGHT_BOUND),
+                    rng.gen_range(CHARACTER_BOTTOM_BOUND..CHARACTER_TOP_BOUND),
+                )
+            })
+            .find(|destination| {
+                !colliders
+                    .iter()
+                    .any(|collider| collider.contains(*destination))
+            });
+
+        if let Some(destination) = destination {
+            let position = global_transform.translation();
+            transform.translation += destination.extend(position.z) - position;
+        }
+    }
+}
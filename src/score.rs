@@ -0,0 +1,238 @@
+use bevy::{
+    app::{Plugin, Update},
+    ecs::{
+        query::With,
+        schedule::{common_conditions::in_state, IntoSystemConfigs, OnEnter, OnExit},
+        system::{Query, Res, ResMut, Resource},
+    },
+    time::{Stopwatch, Time},
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    characters::{CharacterState, Player, Status},
+    persistence::{Location, PersistAppExt, Persisted},
+    setup::RoundResult,
+    GameState,
+};
+
+/// Number of scores kept in the [`HighScores`] table.
+pub(crate) const HIGH_SCORE_COUNT: usize = 10;
+
+/// Longest name that can be typed for a high score.
+pub(crate) const MAX_NAME_LENGTH: usize = 8;
+
+/// Name recorded for a high score when none has been typed.
+const DEFAULT_NAME: &str = "Player";
+
+const POINTS_PER_PRESENT: u32 = 100;
+const POINTS_PER_HEALTH: u32 = 5;
+/// Rounds won quicker than this earn a time bonus.
+const PAR_TIME_SECONDS: f32 = 60.;
+const POINTS_PER_SECOND_UNDER_PAR: f32 = 10.;
+
+/// Returns the score of a round from the value of the nice presents
+/// collected, see [`crate::characters::Inventory::value_of_presents`], the
+/// health left and the time taken.
+pub(crate) fn score(value_of_presents: u32, health: u8, seconds: f32) -> u32 {
+    let time_bonus = (PAR_TIME_SECONDS - seconds).max(0.) * POINTS_PER_SECOND_UNDER_PAR;
+
+    value_of_presents * POINTS_PER_PRESENT
+        + u32::from(health) * POINTS_PER_HEALTH
+        + time_bonus as u32
+}
+
+/// Time spent playing the current round. The clock stops while the game is
+/// paused, and once every player has won or died.
+#[derive(Resource, Default)]
+pub(crate) struct RoundClock(pub(crate) Stopwatch);
+
+/// A score in the [`HighScores`] table.
+#[derive(Clone, Serialize, Deserialize)]
+pub(crate) struct HighScore {
+    pub(crate) name: String,
+    pub(crate) score: u32,
+    /// Index of the level the score was made on.
+    pub(crate) level: usize,
+}
+
+/// The best scores of the rounds won, highest first.
+#[derive(Resource, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub(crate) struct HighScores {
+    entries: Vec<HighScore>,
+}
+
+impl HighScores {
+    /// Returns the scores in the table, highest first.
+    pub(crate) fn entries(&self) -> &[HighScore] {
+        &self.entries
+    }
+
+    /// Returns `true` if the score is good enough to be added to the table.
+    pub(crate) fn qualifies(&self, score: u32) -> bool {
+        self.entries.len() < HIGH_SCORE_COUNT
+            || self
+                .entries
+                .last()
+                .is_some_and(|lowest| score > lowest.score)
+    }
+
+    /// Add the score to the table, after those that are as high, dropping the
+    /// lowest score if the table is full.
+    pub(crate) fn insert(&mut self, high_score: HighScore) {
+        let index = self
+            .entries
+            .partition_point(|entry| entry.score >= high_score.score);
+        self.entries.insert(index, high_score);
+        self.entries.truncate(HIGH_SCORE_COUNT);
+    }
+}
+
+impl Persisted for HighScores {
+    const FILE: &'static str = "high_scores.ron";
+    const LOCATION: Location = Location::Data;
+    const VERSION: u32 = 1;
+}
+
+/// A new high score waiting for the player to type their name on the end of
+/// round screen.
+#[derive(Resource, Default)]
+pub(crate) struct PendingHighScore(pub(crate) Option<HighScore>);
+
+impl PendingHighScore {
+    /// Add the pending score to the table, under the default name if none has
+    /// been typed.
+    pub(crate) fn submit(&mut self, high_scores: &mut HighScores) {
+        if let Some(mut high_score) = self.0.take() {
+            if high_score.name.is_empty() {
+                high_score.name = DEFAULT_NAME.to_string();
+            }
+            high_scores.insert(high_score);
+        }
+    }
+}
+
+/// Returns `true` while the name of a new high score is being typed, so that
+/// the keys pressed aren't used for anything else.
+pub(crate) fn typing_high_score_name(pending_high_score: Res<PendingHighScore>) -> bool {
+    pending_high_score.0.is_some()
+}
+
+/// Plugin timing the rounds and keeping the table of [`HighScores`], saved in
+/// the user's data directory.
+pub(crate) struct Scoring;
+
+impl Plugin for Scoring {
+    fn build(&self, app: &mut bevy::prelude::App) {
+        app.init_resource::<RoundClock>()
+            .init_resource::<PendingHighScore>()
+            .init_persisted_resource::<HighScores>()
+            .add_systems(OnEnter(GameState::Game), reset_round_clock)
+            .add_systems(Update, tick_round_clock.run_if(in_state(GameState::Game)))
+            .add_systems(OnEnter(GameState::LevelComplete), check_high_score)
+            .add_systems(OnEnter(GameState::Win), check_high_score)
+            .add_systems(OnExit(GameState::LevelComplete), submit_high_score)
+            .add_systems(OnExit(GameState::Win), submit_high_score);
+    }
+}
+
+fn reset_round_clock(mut round_clock: ResMut<RoundClock>) {
+    round_clock.0.reset();
+    round_clock.0.unpause();
+}
+
+/// Advance the round clock, stopping it for good once every player is dead
+/// or celebrating, so a player still going after the other has died keeps
+/// the clock running.
+fn tick_round_clock(
+    time: Res<Time>,
+    mut round_clock: ResMut<RoundClock>,
+    player_query: Query<&Status, With<Player>>,
+) {
+    // The players are spawned once the level has loaded
+    let playing = player_query
+        .iter()
+        .any(|status| status.state() == CharacterState::Alive && !status.game_over);
+    if !player_query.is_empty() && !playing {
+        round_clock.0.pause();
+    }
+
+    round_clock.0.tick(time.delta());
+}
+
+/// Offer to record the score of the round just won if it makes it into the
+/// table.
+pub(crate) fn check_high_score(
+    round_result: Res<RoundResult>,
+    high_scores: Res<HighScores>,
+    mut pending_high_score: ResMut<PendingHighScore>,
+) {
+    if high_scores.qualifies(round_result.score) {
+        pending_high_score.0 = Some(HighScore {
+            name: String::new(),
+            score: round_result.score,
+            level: round_result.level,
+        });
+    }
+}
+
+/// Record a high score left without a name when leaving the end of round
+/// screen.
+fn submit_high_score(
+    mut pending_high_score: ResMut<PendingHighScore>,
+    mut high_scores: ResMut<HighScores>,
+) {
+    pending_high_score.submit(&mut high_scores);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn high_score(name: &str, score: u32) -> HighScore {
+        HighScore {
+            name: name.to_string(),
+            score,
+            level: 0,
+        }
+    }
+
+    fn names(high_scores: &HighScores) -> Vec<&str> {
+        high_scores
+            .entries()
+            .iter()
+            .map(|entry| entry.name.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn full_table_keeps_the_best_scores() {
+        let mut high_scores = HighScores::default();
+        for score in 1..=HIGH_SCORE_COUNT as u32 {
+            assert!(high_scores.qualifies(score));
+            high_scores.insert(high_score("Player", score * 100));
+        }
+
+        // Once full, a score must beat the lowest one to qualify
+        assert!(!high_scores.qualifies(50));
+        assert!(!high_scores.qualifies(100));
+        assert!(high_scores.qualifies(101));
+
+        high_scores.insert(high_score("Best", 1100));
+        assert_eq!(high_scores.entries().len(), HIGH_SCORE_COUNT);
+        assert_eq!(high_scores.entries()[0].score, 1100);
+        assert_eq!(high_scores.entries().last().unwrap().score, 200);
+    }
+
+    #[test]
+    fn ties_are_kept_in_the_order_they_were_made() {
+        let mut high_scores = HighScores::default();
+        high_scores.insert(high_score("First", 500));
+        high_scores.insert(high_score("Low", 100));
+        high_scores.insert(high_score("Second", 500));
+        high_scores.insert(high_score("High", 900));
+
+        assert_eq!(names(&high_scores), ["High", "First", "Second", "Low"]);
+    }
+}
@@ -0,0 +1,198 @@
+use bevy::{
+    app::{Plugin, Update},
+    ecs::{
+        change_detection::DetectChanges,
+        query::With,
+        system::{Query, Res, Resource},
+    },
+    window::{PrimaryWindow, Window},
+};
+use serde::{Deserialize, Serialize};
+
+use crate::persistence::{Location, PersistAppExt, Persisted};
+
+/// Amount the volumes change by with each step on the settings screen.
+const VOLUME_STEP: f32 = 0.1;
+
+/// Largest integer scale of the window, the smallest being 1x.
+pub(crate) const MAX_WINDOW_SCALE: u8 = 4;
+
+/// The background music tracks shipped with the game.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub(crate) enum MusicTrack {
+    NaughtyNNice,
+    WindlessSlopes,
+}
+
+impl MusicTrack {
+    /// Returns the path of the sound file of this track.
+    pub(crate) fn path(&self) -> &'static str {
+        match self {
+            MusicTrack::NaughtyNNice => "sounds/Naughty_n_Nice.ogg",
+            MusicTrack::WindlessSlopes => "sounds/Windless Slopes.ogg",
+        }
+    }
+
+    /// Name of the track shown to the player.
+    pub(crate) fn label(&self) -> &'static str {
+        match self {
+            MusicTrack::NaughtyNNice => "Naughty n Nice",
+            MusicTrack::WindlessSlopes => "Windless Slopes",
+        }
+    }
+
+    /// Returns the other track.
+    fn toggled(&self) -> Self {
+        match self {
+            MusicTrack::NaughtyNNice => MusicTrack::WindlessSlopes,
+            MusicTrack::WindlessSlopes => MusicTrack::NaughtyNNice,
+        }
+    }
+}
+
+/// Audio and display settings, changed from the settings screen.
+#[derive(Resource, Clone, PartialEq, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub(crate) struct Settings {
+    /// Volume of the background music, from 0 to 1.
+    pub(crate) music_volume: f32,
+    /// Volume of the sound effects, from 0 to 1.
+    pub(crate) sfx_volume: f32,
+    /// Track played during rounds, the menus having their own.
+    pub(crate) music_track: MusicTrack,
+    /// Whether the audio has been muted with the key bound to
+    /// [`crate::control_input::InputAction::Mute`].
+    pub(crate) muted: bool,
+    /// Integer scale of the fixed resolution window, from 1 to
+    /// [`MAX_WINDOW_SCALE`].
+    pub(crate) window_scale: u8,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            music_volume: 1.,
+            sfx_volume: 1.,
+            music_track: MusicTrack::WindlessSlopes,
+            muted: false,
+            window_scale: 1,
+        }
+    }
+}
+
+impl Settings {
+    /// Raise or lower the music volume by the given number of steps.
+    pub(crate) fn step_music_volume(&mut self, steps: i8) {
+        self.music_volume = step_volume(self.music_volume, steps);
+    }
+
+    /// Raise or lower the sound effects volume by the given number of steps.
+    pub(crate) fn step_sfx_volume(&mut self, steps: i8) {
+        self.sfx_volume = step_volume(self.sfx_volume, steps);
+    }
+
+    /// Switch to the other background music track.
+    pub(crate) fn toggle_music_track(&mut self) {
+        self.music_track = self.music_track.toggled();
+    }
+
+    /// Grow or shrink the window by the given number of steps.
+    pub(crate) fn step_window_scale(&mut self, steps: i8) {
+        self.window_scale = self
+            .window_scale
+            .saturating_add_signed(steps)
+            .clamp(1, MAX_WINDOW_SCALE);
+    }
+}
+
+/// Change a volume by the given number of steps, keeping it between 0 and 1.
+/// The volume is rounded to the nearest step so that it can get back to 0.
+fn step_volume(volume: f32, steps: i8) -> f32 {
+    let volume = volume + f32::from(steps) * VOLUME_STEP;
+    ((volume / VOLUME_STEP).round() * VOLUME_STEP).clamp(0., 1.)
+}
+
+impl Persisted for Settings {
+    const FILE: &'static str = "settings.ron";
+    const LOCATION: Location = Location::Config;
+    const VERSION: u32 = 1;
+
+    /// Keep the volumes and window scale within the ranges the settings
+    /// screen allows.
+    fn sanitized(self) -> Self {
+        let default = Self::default();
+        let volume = |volume: f32, default: f32| {
+            if volume.is_nan() {
+                default
+            } else {
+                volume.clamp(0., 1.)
+            }
+        };
+
+        Self {
+            music_volume: volume(self.music_volume, default.music_volume),
+            sfx_volume: volume(self.sfx_volume, default.sfx_volume),
+            window_scale: self.window_scale.clamp(1, MAX_WINDOW_SCALE),
+            ..self
+        }
+    }
+}
+
+/// Plugin loading the [`Settings`] at startup, applying them to the window
+/// whenever they change, and saving them. The audio settings are applied by
+/// [`crate::game_audio::GameAudio`].
+pub(crate) struct SettingsPlugin;
+
+impl Plugin for SettingsPlugin {
+    fn build(&self, app: &mut bevy::prelude::App) {
+        app.init_persisted_resource::<Settings>()
+            .add_systems(Update, apply_window_scale);
+    }
+}
+
+/// Scale the window by the integer scale from the [`Settings`], keeping its
+/// logical resolution.
+fn apply_window_scale(
+    settings: Res<Settings>,
+    mut window_query: Query<&mut Window, With<PrimaryWindow>>,
+) {
+    if !settings.is_changed() {
+        return;
+    }
+
+    if let Ok(mut window) = window_query.get_single_mut() {
+        window
+            .resolution
+            .set_scale_factor_override(Some(settings.window_scale.into()));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sanitized_clamps_hand_edited_values() {
+        let settings = Settings {
+            music_volume: 3.,
+            sfx_volume: f32::NAN,
+            window_scale: 0,
+            ..Settings::default()
+        }
+        .sanitized();
+
+        assert_eq!(settings.music_volume, 1.);
+        assert_eq!(settings.sfx_volume, Settings::default().sfx_volume);
+        assert_eq!(settings.window_scale, 1);
+
+        let settings = Settings {
+            music_volume: -1.,
+            window_scale: 12,
+            ..Settings::default()
+        }
+        .sanitized();
+
+        assert_eq!(settings.music_volume, 0.);
+        assert_eq!(settings.window_scale, MAX_WINDOW_SCALE);
+    }
+}
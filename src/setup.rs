@@ -1,6 +1,8 @@
 mod initial_setup;
+mod ldtk_entities;
 
-pub(crate) use initial_setup::InitialSetup;
+pub(crate) use initial_setup::{InitialSetup, RoundResult};
+pub(crate) use ldtk_entities::{level_transform, EnemyStart, PlayerStart};
 
 pub(crate) use initial_setup::{
     CHARACTER_BOTTOM_BOUND, CHARACTER_LEFT_BOUND, CHARACTER_RIGHT_BOUND, CHARACTER_TOP_BOUND,
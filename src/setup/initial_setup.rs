@@ -1,14 +1,30 @@
 use std::ops::Range;
 
-use crate::animation::{AnimateSprite, Animated, AnimationIndices, AnimationTimer, PingPong};
-use crate::characters::{BasicCharacter, CharacterWithStatus, Direction, Inventory, Status};
+use crate::ai::Ai;
+use crate::animation::AnimateSprite;
+use crate::campaign::{select_campaign_level, Campaign, CampaignPlugin};
+use crate::characters::{
+    load_sprite_sheets, ActiveEffects, BasicCharacter, CharacterState, CharacterWithStatus, Effect,
+    Inventory, Player, SpriteSheet, SpriteSheetAtlases, Status, SPEED_BOOST_FACTOR,
+};
 use crate::collision::CollisionHandler;
 use crate::control_input::ControlInput;
-use crate::game_audio::Audio;
-use crate::markers::{CameraMarker, CharacterMarker};
-use crate::moveable::{Moveable, Speed};
-use crate::present::{Present, PresentType};
-use crate::{WINDOW_HEIGHT, WINDOW_WIDTH};
+use crate::enemy::Enemies;
+use crate::events::{GameEvents, PlayerDamaged, PlayerDied, PlayerHealed, PresentCollected};
+use crate::game_audio::GameAudio;
+use crate::level_config::{LevelConfig, LevelConfigHandle, LevelConfigPlugin};
+use crate::markers::{CameraMarker, OnGameScreen};
+use crate::menu::despawn_screen;
+use crate::moveable::{Moveable, Movement, Speed};
+use crate::pause::{GamePause, PauseState};
+use crate::play_mode::PlayMode;
+use crate::present::{MovingPresents, Present};
+use crate::score::{score, RoundClock, Scoring};
+use crate::settings::SettingsPlugin;
+use crate::tile_collision::TileCollision;
+use crate::{GameState, WINDOW_HEIGHT, WINDOW_WIDTH};
+
+use super::ldtk_entities::{level_transform, LdtkEntities, PlayerStart};
 use bevy::prelude::*;
 
 use bevy::utils::HashSet;
@@ -21,18 +37,27 @@ use rand::Rng;
 
 pub(crate) const WALL_THICKNESS: f32 = 10.0;
 
-// x coordinates
-pub(crate) const LEFT_WALL: f32 = -450.;
-pub(crate) const RIGHT_WALL: f32 = 450.;
-// y coordinates
-pub(crate) const BOTTOM_WALL: f32 = -300.;
-pub(crate) const TOP_WALL: f32 = 300.;
+/// How fast the player walks, in pixels per second.
+const PLAYER_SPEED: f32 = 120.;
+
+/// Where the second player starts, relative to the first.
+const SECOND_PLAYER_OFFSET: Vec3 = Vec3::new(48., 0., 0.);
+
+/// Tint of each player's deer, the second one is shaded so that the players
+/// can be told apart from each other and from the flamingo enemies.
+const PLAYER_TINTS: [Color; 2] = [Color::WHITE, Color::rgb(0.6, 0.8, 1.0)];
 
 const SCOREBOARD_FONT_SIZE: f32 = 20.0;
 const SCORE_BASIC_TEXT_PADDING: Val = Val::Px(10.0);
 const SCORE_NAUGHTY_TEXT_PADDING_LEFT: Val = Val::Px(WINDOW_WIDTH - 120.);
+const SCORE_TIME_TEXT_PADDING_LEFT: Val = Val::Px(WINDOW_WIDTH / 2. - 40.);
+const SCORE_SECOND_PLAYER_TEXT_PADDING_LEFT: Val = Val::Px(WINDOW_WIDTH - 150.);
+const SCORE_SECOND_ROW_TEXT_PADDING_TOP: Val = Val::Px(30.);
+const SCORE_THIRD_ROW_TEXT_PADDING_TOP: Val = Val::Px(50.);
+
+/// The countdown turns red for the last seconds of a timed round.
+const COUNTDOWN_WARNING_SECONDS: f32 = 10.;
 
-const WALL_COLOR: Color = Color::rgb(0.8, 0.8, 0.8);
 const BACKGROUND_COLOR: Color = Color::rgb(0.9, 0.9, 0.9);
 const TEXT_COLOR: Color = Color::rgb(0.2, 0.2, 0.9);
 const RED_TEXT_COLOR: Color = Color::rgb(0.9, 0.2, 0.5);
@@ -50,35 +75,175 @@ const X_RANGE: Range<i32> =
 const Y_RANGE: Range<i32> =
     ((CHARACTER_BOTTOM_BOUND / 10.) as i32)..((CHARACTER_TOP_BOUND / 10.) as i32);
 
+/// Number of random locations tried for each present before giving up on
+/// spawning it, in case the level has no free room left.
+const PRESENT_SPAWN_ATTEMPTS: usize = 100;
+
+/// Final statistics of the last round played, shown on the end of round
+/// screens.
+#[derive(Resource, Default)]
+pub(crate) struct RoundResult {
+    /// Presents collected in the round, or in the whole campaign once the
+    /// last level has been won.
+    pub(crate) presents: u8,
+    pub(crate) health: u8,
+    /// Score of the round, see [`score`].
+    pub(crate) score: u32,
+    /// Index of the level played.
+    pub(crate) level: usize,
+    /// Index of the player the statistics are for: the winner, or the player
+    /// who collected the most presents when the round is lost.
+    pub(crate) player: usize,
+}
+
+/// Update the scoreboard of the player who collected a present, was hurt or
+/// healed.
 fn update_stats(
-    player_query: Query<(&Status, &Inventory), With<CharacterMarker>>,
-    mut query_nice: Query<&mut Text, With<CounterNice>>,
-    mut query_naughty: Query<&mut Text, (With<Health>, Without<CounterNice>)>,
+    mut present_collected_events: EventReader<PresentCollected>,
+    mut player_damaged_events: EventReader<PlayerDamaged>,
+    mut player_healed_events: EventReader<PlayerHealed>,
+    player_query: Query<&Player>,
+    mut query_nice: Query<(&mut Text, &CounterNice)>,
+    mut query_naughty: Query<(&mut Text, &Health), Without<CounterNice>>,
+) {
+    for event in present_collected_events.iter() {
+        let Ok(player) = player_query.get(event.character) else {
+            continue;
+        };
+
+        for (mut text, counter) in &mut query_nice {
+            if counter.0 == player.index() {
+                text.sections[1].value = event.total.to_string();
+            }
+        }
+    }
+
+    for event in player_damaged_events.iter() {
+        let Ok(player) = player_query.get(event.character) else {
+            continue;
+        };
+
+        for (mut text, health) in &mut query_naughty {
+            if health.0 == player.index() {
+                text.sections[1].value = event.health.to_string();
+                if event.health == 0 {
+                    text.sections[1].style.color = RED_TEXT_COLOR;
+                }
+            }
+        }
+    }
+
+    for event in player_healed_events.iter() {
+        let Ok(player) = player_query.get(event.character) else {
+            continue;
+        };
+
+        for (mut text, health) in &mut query_naughty {
+            if health.0 == player.index() {
+                text.sections[1].value = event.health.to_string();
+            }
+        }
+    }
+}
+
+/// Count down the time left of the [`ActiveEffects`] of the players, putting
+/// their speed back once a speed boost ends.
+fn tick_effects(time: Res<Time>, mut query: Query<(&mut ActiveEffects, &mut Moveable)>) {
+    for (mut active_effects, mut moveable) in &mut query {
+        for effect in active_effects.tick(time.delta()) {
+            if effect == Effect::SpeedBoost {
+                moveable.speed.0 /= SPEED_BOOST_FACTOR;
+            }
+        }
+    }
+}
+
+/// Show the [`ActiveEffects`] of each player on the scoreboard, with the
+/// seconds left for each.
+fn update_effects_text(
+    player_query: Query<(&Player, &ActiveEffects), Changed<ActiveEffects>>,
+    mut query: Query<(&mut Text, &EffectsText)>,
+) {
+    for (player, active_effects) in &player_query {
+        let value = active_effects
+            .iter()
+            .map(|(effect, seconds)| format!("{} {}", effect.label(), seconds.ceil()))
+            .collect::<Vec<_>>()
+            .join("  ");
+
+        for (mut text, effects_text) in &mut query {
+            if effects_text.0 == player.index() && text.sections[1].value != value {
+                text.sections[1].value = value.clone();
+            }
+        }
+    }
+}
+
+/// Count down the time left in a timed round, turning the countdown red for
+/// the last [`COUNTDOWN_WARNING_SECONDS`].
+fn update_countdown(
+    round_clock: Res<RoundClock>,
+    level_config: Res<LevelConfigHandle>,
+    level_configs: Res<Assets<LevelConfig>>,
+    mut query: Query<&mut Text, With<Countdown>>,
 ) {
-    if let Ok((status, inventory)) = player_query.get_single() {
-        let mut text = query_nice.single_mut();
-        text.sections[1].value = inventory.number_of_presents().to_string();
+    let Some(time_limit) = level_config.get(&level_configs).time_limit else {
+        return;
+    };
+    let remaining = (time_limit - round_clock.0.elapsed_secs()).max(0.);
 
-        let mut text = query_naughty.single_mut();
-        text.sections[1].value = status.health.to_string();
-        if status.health == 0 {
+    for mut text in &mut query {
+        let value = format!("{}", remaining.ceil());
+        if text.sections[1].value != value {
+            text.sections[1].value = value;
+        }
+        if remaining <= COUNTDOWN_WARNING_SECONDS {
             text.sections[1].style.color = RED_TEXT_COLOR;
         }
     }
 }
 
-/// Plugin to set up initial scene with camera, player, and audio. Adds plugins
-/// for sprite animation and handling keyboard control of sprite.
+/// Kill the players still playing when the time limit of the round runs out,
+/// so the round is lost once the death animation has played. Players who
+/// already won are left celebrating.
+fn time_out(
+    round_clock: Res<RoundClock>,
+    level_config: Res<LevelConfigHandle>,
+    level_configs: Res<Assets<LevelConfig>>,
+    mut player_query: Query<&mut Status, With<Player>>,
+    mut player_died_events: EventWriter<PlayerDied>,
+) {
+    let Some(time_limit) = level_config.get(&level_configs).time_limit else {
+        return;
+    };
+    if round_clock.0.elapsed_secs() < time_limit {
+        return;
+    }
+
+    for mut status in &mut player_query {
+        if status.state() == CharacterState::Alive && !status.game_over {
+            status.die();
+            player_died_events.send(PlayerDied);
+        }
+    }
+}
+
+/// Plugin to set up initial scene with camera and audio. The level, player,
+/// presents and scoreboard are spawned when entering [`GameState::Game`] and
+/// despawned when leaving it. Adds plugins for sprite animation, movement and
+/// handling keyboard control of sprite.
 pub(crate) struct InitialSetup;
 
 impl Plugin for InitialSetup {
     fn build(&self, app: &mut App) {
-        app.add_systems(Startup, setup_scene)
-            .add_systems(Startup, setup_player)
-            .add_systems(Startup, setup_presents)
-            .add_systems(Startup, setup_audio)
-            .add_systems(Startup, setup_walls)
-            .add_systems(Startup, setup_scoreboard)
+        app.add_systems(Startup, (setup_scene, load_sprite_sheets))
+            .add_systems(
+                OnEnter(GameState::Game),
+                (setup_level, setup_presents, setup_scoreboard).after(select_campaign_level),
+            )
+            .add_systems(OnExit(GameState::Game), despawn_screen::<OnGameScreen>)
+            .init_resource::<RoundResult>()
+            .init_resource::<PlayMode>()
             .insert_resource(ClearColor(BACKGROUND_COLOR))
             .insert_resource(LdtkSettings {
                 level_spawn_behavior: LevelSpawnBehavior::UseWorldTranslation {
@@ -89,345 +254,359 @@ impl Plugin for InitialSetup {
             .insert_resource(LevelSelection::Index(0))
             .add_plugins(LdtkPlugin)
             .add_plugins(AnimateSprite)
+            .add_plugins(Movement)
             .add_plugins(ControlInput)
             .add_plugins(CollisionHandler)
-            .add_systems(Update, (bevy::window::close_on_esc, update_stats, end_game));
+            .add_plugins(GameEvents)
+            .add_plugins(LevelConfigPlugin)
+            .add_plugins(LdtkEntities)
+            .add_plugins(CampaignPlugin)
+            .add_plugins(TileCollision)
+            .add_plugins(GamePause)
+            .add_plugins(SettingsPlugin)
+            .add_plugins(GameAudio)
+            .add_plugins(Scoring)
+            .add_plugins(Enemies)
+            .add_plugins(Ai)
+            .add_plugins(MovingPresents)
+            .add_systems(
+                Update,
+                (
+                    setup_player,
+                    update_stats,
+                    update_countdown,
+                    update_effects_text,
+                    tick_effects.run_if(in_state(PauseState::Running)),
+                    time_out.run_if(in_state(PauseState::Running)),
+                    end_game,
+                )
+                    .run_if(in_state(GameState::Game)),
+            );
     }
 }
 
 /// Add the default 2D camera bundle.
-fn setup_scene(mut commands: Commands, asset_server: Res<AssetServer>) {
+fn setup_scene(mut commands: Commands) {
     commands.spawn((Camera2dBundle::default(), CameraMarker));
+}
 
-    commands.spawn(LdtkWorldBundle {
-        ldtk_handle: asset_server.load("levels/ldtk/Naughty_n_Nice.ldtk"),
-        transform: Transform::from_xyz(0., 0., 0.),
-        ..Default::default()
-    });
+/// Load the LDtk world for the current round.
+fn setup_level(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.spawn((
+        LdtkWorldBundle {
+            ldtk_handle: asset_server.load("levels/ldtk/Naughty_n_Nice.ldtk"),
+            transform: Transform::from_xyz(0., 0., 0.),
+            ..Default::default()
+        },
+        OnGameScreen,
+    ));
 }
 
-/// Setup the player. This will load up the player's sprite sheet and create a
-/// texture atlas from it. The sprite sheet has three frames of animation for
-/// each of the four movement directions. Each frame is displayed at 300ms intervals.
+/// Setup the players at the [`PlayerStart`] placed in the LDtk level, once the
+/// level has spawned, the second player standing next to the first. Both
+/// players are drawn with the deer [`SpriteSheet`], tinted with their colour
+/// from [`PLAYER_TINTS`]. Players are only spawned once per round, so that
+/// reloading the level doesn't add more of them.
+#[allow(clippy::too_many_arguments)]
 fn setup_player(
     mut commands: Commands,
-    asset_server: Res<AssetServer>,
-    mut texture_atlases: ResMut<Assets<TextureAtlas>>,
+    sprite_sheet_atlases: Res<SpriteSheetAtlases>,
+    level_config: Res<LevelConfigHandle>,
+    level_configs: Res<Assets<LevelConfig>>,
+    campaign: Res<Campaign>,
+    play_mode: Res<PlayMode>,
+    player_start_query: Query<(&Transform, &Parent), Added<PlayerStart>>,
+    level_query: Query<&Transform, Without<PlayerStart>>,
+    player_query: Query<(), With<Player>>,
 ) {
-    // Load the sprite sheet
-    let texture_handle = asset_server.load("sprites/deer.png");
-
-    // Create the texture atlas: the sprite sheet is four rows with three animation frames
-    // per row. Each frame is a 48x48 bitmap.
-    let texture_atlas =
-        TextureAtlas::from_grid(texture_handle, Vec2::new(48.0, 48.0), 3, 6, None, None);
-    let texture_atlas_handle = texture_atlases.add(texture_atlas);
-
-    // Set the start and end frames for each sprite animation.
-    let animation_indices = AnimationIndices {
-        forward_start: 9,
-        forward_end: 11,
-        back_start: 0,
-        back_end: 2,
-        left_start: 3,
-        left_end: 5,
-        right_start: 6,
-        right_end: 8,
-        celebrate_start: 12,
-        celebrate_end: 14,
-        die_start: 15,
-        die_end: 17,
+    let Some((start_transform, level)) = player_start_query.iter().next() else {
+        return;
     };
+    if !player_query.is_empty() {
+        return;
+    }
+
+    // The player start is relative to its level, the player lives in world space
+    let start = level_transform(&level_query, level).transform_point(start_transform.translation);
 
-    // Spawn the character in the scene. Character will start facing the camera.
-    commands.spawn(CharacterWithStatus {
-        basic_character: BasicCharacter {
-            animated: Animated {
-                sprite_sheet_bundle: SpriteSheetBundle {
-                    texture_atlas: texture_atlas_handle,
-                    sprite: TextureAtlasSprite::new(animation_indices.back_start + 1),
-                    transform: Transform::from_xyz(0., 0., 10.),
-                    ..default()
+    let level_config = level_config.get(&level_configs);
+
+    let texture_atlas_handle = sprite_sheet_atlases.get(SpriteSheet::Deer);
+
+    for (index, tint) in PLAYER_TINTS
+        .into_iter()
+        .enumerate()
+        .take(play_mode.player_count())
+    {
+        let position = Vec3::new(start.x, start.y, 10.) + SECOND_PLAYER_OFFSET * index as f32;
+        let mut animated = SpriteSheet::Deer.animated(texture_atlas_handle.clone(), position);
+        animated.sprite_sheet_bundle.sprite.color = tint;
+
+        // Spawn the character in the scene. Character will start facing the camera.
+        commands.spawn((
+            CharacterWithStatus {
+                basic_character: BasicCharacter {
+                    animated,
+                    character_marker: crate::markers::CharacterMarker,
+                    moveable: Moveable::new(Speed(PLAYER_SPEED)),
                 },
-                animation_indices,
-                animation_timer: AnimationTimer(Timer::from_seconds(0.3, TimerMode::Repeating)),
-                ping_pong: PingPong::Ping,
+                status: Status::new(campaign.starting_health(level_config))
+                    .with_max_health(level_config.starting_health),
+                inventory: Inventory::new(),
+                active_effects: ActiveEffects::default(),
             },
-            character_marker: crate::markers::CharacterMarker,
-            moveable: Moveable {
-                direction: Direction::Static,
-                speed: Speed(1.0),
-            },
-        },
-        status: Status::new(100),
-        inventory: Inventory::new(),
-    });
+            Player::new(index),
+            OnGameScreen,
+        ));
+    }
 }
 
-/// Randomly spawn presents.
-fn setup_presents(mut commands: Commands, asset_server: Res<AssetServer>) {
+/// Randomly spawn the extra presents listed in the [`LevelConfig`], on top of
+/// those placed in the LDtk level.
+fn setup_presents(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    level_config: Res<LevelConfigHandle>,
+    level_configs: Res<Assets<LevelConfig>>,
+) {
     let mut rng = rand::thread_rng();
-    let red_present = "sprites/Gifts_Red.png".to_string();
-    let green_present = "sprites/Gifts_Green.png".to_string();
 
     let mut locations_spawned = HashSet::new();
     // skip character spawn location
     locations_spawned.insert((0, 0));
 
-    println!("{X_RANGE:?}");
-    println!("{Y_RANGE:?}");
-    for count in 0..10 {
-        let (present_type, current_present_image) = if count < 5 {
-            (PresentType::Naughty(20), &red_present)
-        } else {
-            (PresentType::Nice, &green_present)
-        };
+    // repeat_n is only stable since Rust 1.82
+    #[allow(clippy::manual_repeat_n)]
+    let present_types = level_config
+        .get(&level_configs)
+        .presents
+        .iter()
+        .flat_map(|spawn| std::iter::repeat(spawn.present_type).take(spawn.count.into()))
+        .collect::<Vec<_>>();
 
+    for present_type in present_types {
         // Range is set to a tenth of screen size and then multiplied up to cut down on clustering of presents
-        let mut x = rng.gen_range(X_RANGE) * 10;
-        let mut y = rng.gen_range(Y_RANGE) * 10;
-
-        // loop until we get a unique location
-        while !locations_spawned.insert((x as u32, y as u32)) {
-            x = rng.gen_range(X_RANGE) * 10;
-            y = rng.gen_range(Y_RANGE) * 10;
-        }
-
-        println!("Spawning present at {x:.5}, {y:.5}");
+        let location = (0..PRESENT_SPAWN_ATTEMPTS)
+            .map(|_| (rng.gen_range(X_RANGE) * 10, rng.gen_range(Y_RANGE) * 10))
+            .find(|&(x, y)| locations_spawned.insert((x as u32, y as u32)));
+        let Some((x, y)) = location else {
+            warn!("No room left to spawn a present");
+            continue;
+        };
 
         commands.spawn((
             SpriteBundle {
-                texture: asset_server.load(current_present_image),
+                texture: asset_server.load(present_type.sprite_path()),
                 transform: Transform::from_xyz(x as f32, y as f32, 5.),
                 ..Default::default()
             },
             Present::new(present_type),
+            OnGameScreen,
         ));
     }
 }
 
-/// Load the background audio into the asset server.
-fn setup_audio(asset_server: Res<AssetServer>, mut commands: Commands) {
-    commands.spawn((
-        AudioBundle {
-            source: asset_server.load("sounds/Naughty_n_Nice.ogg"),
-            settings: PlaybackSettings {
-                mode: bevy_audio::PlaybackMode::Loop,
-                ..Default::default()
-            },
-        },
-        Audio,
-    ));
-}
-
-fn setup_walls(mut commands: Commands) {
-    // Walls
-    commands.spawn(WallBundle::new(WallLocation::Left));
-    commands.spawn(WallBundle::new(WallLocation::Right));
-    commands.spawn(WallBundle::new(WallLocation::Bottom));
-    commands.spawn(WallBundle::new(WallLocation::Top));
-}
+/// Scoreboard counter of the presents collected by the [`Player`] with the
+/// given index.
+#[derive(Component)]
+struct CounterNice(usize);
 
+/// Scoreboard counter of the health left to the [`Player`] with the given
+/// index.
 #[derive(Component)]
-struct CounterNice;
+struct Health(usize);
 
+/// Scoreboard list of the [`ActiveEffects`] of the [`Player`] with the given
+/// index.
 #[derive(Component)]
-struct Health;
+struct EffectsText(usize);
 
-fn setup_scoreboard(mut commands: Commands) {
-    // Scoreboard: present counters
-    commands.spawn((
-        TextBundle::from_sections([
-            TextSection::new(
-                "Presents: ",
-                TextStyle {
-                    font_size: SCOREBOARD_FONT_SIZE,
-                    color: TEXT_COLOR,
-                    ..default()
-                },
-            ),
-            TextSection::from_style(TextStyle {
+#[derive(Component)]
+struct Countdown;
+
+/// Returns a scoreboard text, with its label and value, at the given position.
+fn scoreboard_text(label: String, value: String, left: Val, top: Val) -> TextBundle {
+    TextBundle::from_sections([
+        TextSection::new(
+            label,
+            TextStyle {
                 font_size: SCOREBOARD_FONT_SIZE,
-                color: SCORE_COLOR,
+                color: TEXT_COLOR,
                 ..default()
-            }),
-        ])
-        .with_style(Style {
-            position_type: PositionType::Absolute,
-            top: SCORE_BASIC_TEXT_PADDING,
-            left: SCORE_BASIC_TEXT_PADDING,
-            ..default()
-        }),
-        CounterNice,
-    ));
-    commands.spawn((
-        TextBundle::from_sections([
-            TextSection::new(
-                "Health: ",
-                TextStyle {
-                    font_size: SCOREBOARD_FONT_SIZE,
-                    color: TEXT_COLOR,
-                    ..default()
-                },
-            ),
-            TextSection::from_style(TextStyle {
+            },
+        ),
+        TextSection::new(
+            value,
+            TextStyle {
                 font_size: SCOREBOARD_FONT_SIZE,
                 color: SCORE_COLOR,
                 ..default()
-            }),
-        ])
-        .with_style(Style {
-            position_type: PositionType::Absolute,
-            top: SCORE_BASIC_TEXT_PADDING,
-            left: SCORE_NAUGHTY_TEXT_PADDING_LEFT,
-            ..default()
-        }),
-        Health,
-    ));
-}
-
-#[derive(Component)]
-struct Collider;
-
-#[derive(Event, Default)]
-struct CollisionEvent;
-
-/// Which side of the arena is this wall located on?
-enum WallLocation {
-    Left,
-    Right,
-    Bottom,
-    Top,
+            },
+        ),
+    ])
+    .with_style(Style {
+        position_type: PositionType::Absolute,
+        top,
+        left,
+        ..default()
+    })
 }
 
-impl WallLocation {
-    fn position(&self) -> Vec2 {
-        match self {
-            WallLocation::Left => Vec2::new(LEFT_WALL, 0.),
-            WallLocation::Right => Vec2::new(RIGHT_WALL, 0.),
-            WallLocation::Bottom => Vec2::new(0., BOTTOM_WALL),
-            WallLocation::Top => Vec2::new(0., TOP_WALL),
-        }
-    }
-
-    fn size(&self) -> Vec2 {
-        let arena_height = TOP_WALL - BOTTOM_WALL;
-        let arena_width = RIGHT_WALL - LEFT_WALL;
-        // Make sure we haven't messed up our constants
-        assert!(arena_height > 0.0);
-        assert!(arena_width > 0.0);
+fn setup_scoreboard(
+    mut commands: Commands,
+    level_config: Res<LevelConfigHandle>,
+    level_configs: Res<Assets<LevelConfig>>,
+    campaign: Res<Campaign>,
+    play_mode: Res<PlayMode>,
+) {
+    let starting_health = campaign
+        .starting_health(level_config.get(&level_configs))
+        .to_string();
+    let player_count = play_mode.player_count();
+
+    // Scoreboard: present counters for each player, starting from an empty
+    // inventory and full health, and the effects active on them. These are
+    // updated by `update_stats`. With two players, each has their counters
+    // stacked on their side of the screen.
+    for index in 0..player_count {
+        let (prefix, presents_position, health_position, effects_position) = if player_count == 1 {
+            (
+                String::new(),
+                (SCORE_BASIC_TEXT_PADDING, SCORE_BASIC_TEXT_PADDING),
+                (SCORE_NAUGHTY_TEXT_PADDING_LEFT, SCORE_BASIC_TEXT_PADDING),
+                (SCORE_BASIC_TEXT_PADDING, SCORE_SECOND_ROW_TEXT_PADDING_TOP),
+            )
+        } else {
+            let left = if index == 0 {
+                SCORE_BASIC_TEXT_PADDING
+            } else {
+                SCORE_SECOND_PLAYER_TEXT_PADDING_LEFT
+            };
+            (
+                format!("P{} ", index + 1),
+                (left, SCORE_BASIC_TEXT_PADDING),
+                (left, SCORE_SECOND_ROW_TEXT_PADDING_TOP),
+                (left, SCORE_THIRD_ROW_TEXT_PADDING_TOP),
+            )
+        };
 
-        match self {
-            WallLocation::Left | WallLocation::Right => {
-                Vec2::new(WALL_THICKNESS, arena_height + WALL_THICKNESS)
-            }
-            WallLocation::Bottom | WallLocation::Top => {
-                Vec2::new(arena_width + WALL_THICKNESS, WALL_THICKNESS)
-            }
-        }
+        commands.spawn((
+            scoreboard_text(
+                format!("{prefix}Presents: "),
+                "0".to_string(),
+                presents_position.0,
+                presents_position.1,
+            ),
+            CounterNice(index),
+            OnGameScreen,
+        ));
+        commands.spawn((
+            scoreboard_text(
+                format!("{prefix}Health: "),
+                starting_health.clone(),
+                health_position.0,
+                health_position.1,
+            ),
+            Health(index),
+            OnGameScreen,
+        ));
+        // Empty until a present starts an effect, updated by
+        // `update_effects_text`
+        commands.spawn((
+            scoreboard_text(
+                prefix.clone(),
+                String::new(),
+                effects_position.0,
+                effects_position.1,
+            ),
+            EffectsText(index),
+            OnGameScreen,
+        ));
     }
-}
 
-// This bundle is a collection of the components that define a "wall" in our game
-#[derive(Bundle)]
-struct WallBundle {
-    // You can nest bundles inside of other bundles like this
-    // Allowing you to compose their functionality
-    sprite_bundle: SpriteBundle,
-    collider: Collider,
-}
-
-impl WallBundle {
-    // This "builder method" allows us to reuse logic across our wall entities,
-    // making our code easier to read and less prone to bugs when we change the logic
-    fn new(location: WallLocation) -> WallBundle {
-        WallBundle {
-            sprite_bundle: SpriteBundle {
-                transform: Transform {
-                    // We need to convert our Vec2 into a Vec3, by giving it a z-coordinate
-                    // This is used to determine the order of our sprites
-                    translation: location.position().extend(0.0),
-                    // The z-scale of 2D objects must always be 1.0,
-                    // or their ordering will be affected in surprising ways.
-                    // See https://github.com/bevyengine/bevy/issues/4149
-                    scale: location.size().extend(1.0),
-                    ..default()
-                },
-                sprite: Sprite {
-                    color: WALL_COLOR,
-                    ..default()
-                },
-                ..default()
-            },
-            collider: Collider,
-        }
+    // Time left in the round, only shown if the round is timed. This is
+    // updated by `update_countdown`.
+    if let Some(time_limit) = level_config.get(&level_configs).time_limit {
+        commands.spawn((
+            scoreboard_text(
+                "Time: ".to_string(),
+                format!("{}", time_limit.ceil()),
+                SCORE_TIME_TEXT_PADDING_LEFT,
+                SCORE_BASIC_TEXT_PADDING,
+            ),
+            Countdown,
+            OnGameScreen,
+        ));
     }
 }
 
-// Check for game over you win
+/// Check for game over and move on to the lose screen, the next level of the
+/// [`Campaign`], or the win screen once the last level has been won. The round
+/// is won as soon as one player has finished celebrating, and lost once every
+/// player's death animation has played. In a [`PlayMode::Cooperative`] game
+/// the presents collected by both players count. Only checks when the
+/// [`Status`] of a player changed this frame.
 fn end_game(
-    mut commands: Commands,
-    asset_server: Res<AssetServer>,
-    player_query: Query<(&mut Status, &mut Inventory), With<CharacterMarker>>,
+    player_query: Query<(&Player, &Status, &Inventory)>,
+    changed_query: Query<(), (With<Player>, Changed<Status>)>,
+    mut game_state: ResMut<NextState<GameState>>,
+    mut round_result: ResMut<RoundResult>,
+    mut campaign: ResMut<Campaign>,
+    round_clock: Res<RoundClock>,
+    play_mode: Res<PlayMode>,
 ) {
-    let Ok((status, inventory)) = player_query.get_single() else {
+    if changed_query.is_empty() {
         return;
-    };
+    }
 
-    if !status.game_over {
+    let winner = player_query
+        .iter()
+        .find(|(_, status, _)| status.game_over && status.state() != CharacterState::Dead);
+    let all_over = player_query.iter().all(|(_, status, _)| status.game_over);
+
+    // When the round is lost, the result is that of the player who collected
+    // the most presents
+    let Some((player, status, inventory)) = winner.or_else(|| {
+        player_query
+            .iter()
+            .max_by_key(|(_, _, inventory)| inventory.number_of_presents())
+            .filter(|_| all_over)
+    }) else {
         return;
-    }
+    };
 
-    let (color, text) = if inventory.number_of_presents() == 5 {
-        (TEXT_COLOR, "You win!!")
+    let (presents, value_of_presents) = if *play_mode == PlayMode::Cooperative {
+        player_query
+            .iter()
+            .fold((0_u8, 0_u32), |(presents, value), (_, _, inventory)| {
+                (
+                    presents.saturating_add(inventory.number_of_presents()),
+                    value.saturating_add(inventory.value_of_presents()),
+                )
+            })
     } else {
-        //(RED_TEXT_COLOR, "Oh no! You lost!")
-        return; // haven't got the animation completing before you lost screen comes up so ignore for now
+        (
+            inventory.number_of_presents(),
+            inventory.value_of_presents(),
+        )
     };
 
-    commands
-        .spawn((NodeBundle {
-            style: Style {
-                align_self: AlignSelf::Stretch,
-                align_items: AlignItems::Start,
-                justify_content: JustifyContent::Center,
-                ..default()
-            },
-            ..default()
-        },))
-        .with_children(|parent| {
-            // Display splash bitmap
-            let splash_image: Handle<Image> = asset_server.load("images/splash.png");
-            parent
-                .spawn(ImageBundle {
-                    style: Style {
-                        align_self: AlignSelf::End,
-                        width: Val::Px(480.),
-                        height: Val::Px(288.),
-                        justify_content: JustifyContent::SpaceAround,
-                        flex_direction: FlexDirection::Column,
-                        ..Default::default()
-                    },
-                    image: UiImage::new(splash_image),
-                    ..default()
-                })
-                .with_children(|parent| {
-                    // Display the game name
-                    parent.spawn(
-                        TextBundle::from_section(
-                            text,
-                            TextStyle {
-                                font_size: 35.0,
-                                color,
-                                ..default()
-                            },
-                        )
-                        .with_style(Style {
-                            margin: UiRect::all(Val::Px(10.0)),
-                            align_self: AlignSelf::End,
-                            ..default()
-                        }),
-                    );
-                });
-        });
+    round_result.presents = presents;
+    round_result.health = status.health;
+    round_result.score = score(
+        value_of_presents,
+        status.health,
+        round_clock.0.elapsed_secs(),
+    );
+    round_result.level = campaign.current_level();
+    round_result.player = player.index();
+
+    if status.state() == CharacterState::Dead {
+        game_state.set(GameState::Lose);
+    } else if campaign.complete_level(status.health, presents) {
+        round_result.presents = campaign.presents_collected();
+        game_state.set(GameState::Win);
+    } else {
+        game_state.set(GameState::LevelComplete);
+    }
 }
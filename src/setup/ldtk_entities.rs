@@ -0,0 +1,164 @@
+use bevy::{
+    app::Plugin,
+    asset::{AssetServer, Assets, Handle},
+    ecs::{bundle::Bundle, component::Component, query::ReadOnlyWorldQuery, system::Query},
+    hierarchy::Parent,
+    math::{IVec2, Vec2},
+    prelude::{Image, SpriteBundle},
+    sprite::TextureAtlas,
+    transform::components::Transform,
+};
+use bevy_ecs_ldtk::{
+    prelude::{
+        EntityInstance, LayerInstance, LdtkEntity, LdtkEntityAppExt, LdtkFields, TilesetDefinition,
+    },
+    utils::{grid_coords_to_translation, ldtk_grid_coords_to_grid_coords},
+};
+
+use crate::present::{Present, PresentType};
+
+/// Damage done by a naughty present placed without a `Damage` field.
+const DEFAULT_NAUGHTY_DAMAGE: u8 = 20;
+
+/// Health restored by a healing present placed without a `Health` field.
+const DEFAULT_HEALING: u8 = 20;
+
+/// Damage done by an enemy placed without a `Damage` field.
+const DEFAULT_ENEMY_DAMAGE: u8 = 10;
+
+/// Registers the entities that level designers place in the LDtk editor.
+pub(crate) struct LdtkEntities;
+
+impl Plugin for LdtkEntities {
+    fn build(&self, app: &mut bevy::prelude::App) {
+        app.register_ldtk_entity::<PlayerStartBundle>("PlayerStart")
+            .register_ldtk_entity::<PresentBundle>("NicePresent")
+            .register_ldtk_entity::<PresentBundle>("NaughtyPresent")
+            .register_ldtk_entity::<PresentBundle>("HealingPresent")
+            .register_ldtk_entity::<PresentBundle>("SpeedPresent")
+            .register_ldtk_entity::<PresentBundle>("ShieldPresent")
+            .register_ldtk_entity::<PresentBundle>("GoldenPresent")
+            .register_ldtk_entity::<EnemyStartBundle>("Enemy");
+    }
+}
+
+/// Marker for the location the player starts the level at. The player
+/// itself is spawned by [`super::InitialSetup`] once this has been added.
+#[derive(Component, Default)]
+pub(crate) struct PlayerStart;
+
+#[derive(Bundle, LdtkEntity, Default)]
+struct PlayerStartBundle {
+    player_start: PlayerStart,
+}
+
+/// Location an enemy starts the level at, with the waypoints it patrols
+/// through and the damage it does on contact. The enemy itself is spawned by
+/// [`crate::enemy::Enemies`] once this has been added.
+#[derive(Component)]
+pub(crate) struct EnemyStart {
+    /// Waypoints from the `Waypoints` field, relative to the level like the
+    /// start itself.
+    pub(crate) waypoints: Vec<Vec2>,
+    pub(crate) damage: u8,
+}
+
+#[derive(Bundle)]
+struct EnemyStartBundle {
+    enemy_start: EnemyStart,
+}
+
+impl LdtkEntity for EnemyStartBundle {
+    fn bundle_entity(
+        entity_instance: &EntityInstance,
+        layer_instance: &LayerInstance,
+        _: Option<&Handle<Image>>,
+        _: Option<&TilesetDefinition>,
+        _: &AssetServer,
+        _: &mut Assets<TextureAtlas>,
+    ) -> Self {
+        // Points are given in LDtk cells, counted from the top of the level
+        let waypoints = entity_instance
+            .iter_points_field("Waypoints")
+            .map(|points| {
+                points
+                    .map(|point| {
+                        grid_coords_to_translation(
+                            ldtk_grid_coords_to_grid_coords(*point, layer_instance.c_hei),
+                            IVec2::splat(layer_instance.grid_size),
+                        )
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        EnemyStartBundle {
+            enemy_start: EnemyStart {
+                waypoints,
+                damage: entity_instance
+                    .get_int_field("Damage")
+                    .map_or(DEFAULT_ENEMY_DAMAGE, |damage| {
+                        (*damage).clamp(0, u8::MAX.into()) as u8
+                    }),
+            },
+        }
+    }
+}
+
+/// A [`Present`] placed in the level, drawn with the sprite matching its
+/// [`PresentType`].
+#[derive(Bundle)]
+struct PresentBundle {
+    sprite_bundle: SpriteBundle,
+    present: Present,
+}
+
+impl LdtkEntity for PresentBundle {
+    fn bundle_entity(
+        entity_instance: &EntityInstance,
+        _: &LayerInstance,
+        _: Option<&Handle<Image>>,
+        _: Option<&TilesetDefinition>,
+        asset_server: &AssetServer,
+        _: &mut Assets<TextureAtlas>,
+    ) -> Self {
+        let present_type = match entity_instance.identifier.as_str() {
+            "NaughtyPresent" => PresentType::Naughty(
+                entity_instance
+                    .get_int_field("Damage")
+                    .map_or(DEFAULT_NAUGHTY_DAMAGE, |damage| {
+                        (*damage).clamp(0, u8::MAX.into()) as u8
+                    }),
+            ),
+            "HealingPresent" => PresentType::Healing(
+                entity_instance
+                    .get_int_field("Health")
+                    .map_or(DEFAULT_HEALING, |health| {
+                        (*health).clamp(0, u8::MAX.into()) as u8
+                    }),
+            ),
+            "SpeedPresent" => PresentType::SpeedBoost,
+            "ShieldPresent" => PresentType::Shield,
+            "GoldenPresent" => PresentType::Golden,
+            _ => PresentType::Nice,
+        };
+
+        PresentBundle {
+            sprite_bundle: SpriteBundle {
+                texture: asset_server.load(present_type.sprite_path()),
+                ..Default::default()
+            },
+            present: Present::new(present_type),
+        }
+    }
+}
+
+/// Returns the transform of the level an entity placed in the LDtk level
+/// belongs to, taking positions relative to the level to world space. The
+/// identity is returned if the level can't be found.
+pub(crate) fn level_transform<F: ReadOnlyWorldQuery>(
+    level_query: &Query<&Transform, F>,
+    level: &Parent,
+) -> Transform {
+    level_query.get(level.get()).copied().unwrap_or_default()
+}
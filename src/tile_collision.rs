@@ -0,0 +1,214 @@
+use bevy::{
+    app::{Plugin, Update},
+    ecs::{
+        bundle::Bundle,
+        component::Component,
+        entity::Entity,
+        query::Added,
+        system::{Commands, Query},
+    },
+    math::{IVec2, Rect, Vec2, Vec3},
+    transform::{
+        components::{GlobalTransform, Transform},
+        TransformBundle,
+    },
+};
+use bevy_ecs_ldtk::{
+    prelude::{GridCoords, LdtkIntCell, LdtkIntCellAppExt},
+    utils::grid_coords_to_translation_relative_to_tile_layer,
+};
+
+/// Size of the cells of the `Collision` IntGrid layer.
+pub(crate) const TILE_SIZE: f32 = 48.;
+
+/// IntGrid values of the `Collision` layer that block movement.
+const WALL: i32 = 1;
+const FURNITURE: i32 = 2;
+
+/// Size of the box around a character's feet that is tested against the
+/// colliders, so that characters can stand in front of a wall.
+const FEET_SIZE: Vec2 = Vec2::new(24., 12.);
+/// Offset from the centre of a character's sprite to the centre of its feet.
+const FEET_OFFSET: Vec2 = Vec2::new(0., -18.);
+
+/// Turns the cells of the LDtk `Collision` IntGrid layer (walls, furniture)
+/// into colliders that block the movement of characters.
+pub(crate) struct TileCollision;
+
+impl Plugin for TileCollision {
+    fn build(&self, app: &mut bevy::prelude::App) {
+        app.register_ldtk_int_cell_for_layer::<ColliderBundle>("Collision", WALL)
+            .register_ldtk_int_cell_for_layer::<ColliderBundle>("Collision", FURNITURE)
+            .add_systems(Update, locate_colliders);
+    }
+}
+
+/// Marker for a tile that characters can't walk through.
+#[derive(Component, Default)]
+pub(crate) struct Collider;
+
+#[derive(Bundle, LdtkIntCell, Default)]
+struct ColliderBundle {
+    collider: Collider,
+}
+
+/// IntGrid cells are not positioned in the world, give new colliders a
+/// transform relative to their tile layer so their global position can be
+/// found once transforms have been propagated.
+fn locate_colliders(
+    mut commands: Commands,
+    colliders: Query<(Entity, &GridCoords), Added<Collider>>,
+) {
+    for (entity, grid_coords) in &colliders {
+        let translation = grid_coords_to_translation_relative_to_tile_layer(
+            *grid_coords,
+            IVec2::splat(TILE_SIZE as i32),
+        );
+
+        commands
+            .entity(entity)
+            .insert(TransformBundle::from_transform(
+                Transform::from_translation(translation.extend(0.)),
+            ));
+    }
+}
+
+/// Returns the area covered by a collider in the world.
+pub(crate) fn collider_rect(transform: &GlobalTransform) -> Rect {
+    Rect::from_center_size(transform.translation().truncate(), Vec2::splat(TILE_SIZE))
+}
+
+/// Returns the position of the feet of a character at `translation`, the
+/// part of it that collides with the tiles.
+pub(crate) fn feet_position(translation: Vec3) -> Vec2 {
+    translation.truncate() + FEET_OFFSET
+}
+
+/// Returns the area covered by a collider relative to its level, for use on
+/// the frame a level spawns, before the transforms of its colliders have been
+/// propagated.
+pub(crate) fn collider_rect_in_level(grid_coords: GridCoords) -> Rect {
+    let centre = grid_coords_to_translation_relative_to_tile_layer(
+        grid_coords,
+        IVec2::splat(TILE_SIZE as i32),
+    );
+    Rect::from_center_size(centre, Vec2::splat(TILE_SIZE))
+}
+
+/// Returns the box around the feet of a character at `translation`.
+pub(crate) fn feet_rect(translation: Vec3) -> Rect {
+    Rect::from_center_size(feet_position(translation), FEET_SIZE)
+}
+
+/// Move a character at `translation` by `delta`, one axis at a time, so that
+/// when its feet would end up in a collider it stops against the collider on
+/// that axis while still sliding along the other.
+pub(crate) fn move_and_slide(translation: Vec3, delta: Vec2, colliders: &[Rect]) -> Vec3 {
+    let feet = slide_rect(feet_rect(translation), delta, colliders);
+
+    (feet.center() - FEET_OFFSET).extend(translation.z)
+}
+
+/// Move `rect` by `delta`, one axis at a time, stopping against the colliders
+/// on each axis. Used for anything that isn't a character, which collides
+/// with its own box rather than its feet.
+pub(crate) fn slide_rect(rect: Rect, delta: Vec2, colliders: &[Rect]) -> Rect {
+    let rect = slide_axis(rect, Vec2::new(delta.x, 0.), colliders);
+    slide_axis(rect, Vec2::new(0., delta.y), colliders)
+}
+
+/// Move `feet` by `delta` along a single axis, stopping at the edge of the
+/// first collider in the way.
+fn slide_axis(feet: Rect, delta: Vec2, colliders: &[Rect]) -> Rect {
+    if delta == Vec2::ZERO {
+        return feet;
+    }
+
+    let mut moved = Rect {
+        min: feet.min + delta,
+        max: feet.max + delta,
+    };
+
+    for collider in colliders {
+        if moved.intersect(*collider).is_empty() {
+            continue;
+        }
+
+        let push_back = if delta.x > 0. {
+            Vec2::new(collider.min.x - moved.max.x, 0.)
+        } else if delta.x < 0. {
+            Vec2::new(collider.max.x - moved.min.x, 0.)
+        } else if delta.y > 0. {
+            Vec2::new(0., collider.min.y - moved.max.y)
+        } else {
+            Vec2::new(0., collider.max.y - moved.min.y)
+        };
+        moved.min += push_back;
+        moved.max += push_back;
+    }
+
+    moved
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Returns the area covered by a tile centred at `x`, `y`.
+    fn tile(x: f32, y: f32) -> Rect {
+        Rect::from_center_size(Vec2::new(x, y), Vec2::splat(TILE_SIZE))
+    }
+
+    /// The feet of a character at this translation are centred on the origin.
+    const START: Vec3 = Vec3::new(0., 18., 10.);
+
+    #[test]
+    fn move_without_colliders() {
+        let translation = move_and_slide(START, Vec2::new(10., -5.), &[]);
+
+        assert_eq!(translation, Vec3::new(10., 13., 10.));
+    }
+
+    #[test]
+    fn stop_at_wall() {
+        let wall = [tile(100., 0.)];
+
+        let translation = move_and_slide(START, Vec2::new(100., 0.), &wall);
+
+        // The right edge of the feet rests against the left edge of the wall
+        assert_eq!(translation, Vec3::new(64., 18., 10.));
+        assert_eq!(feet_rect(translation).max.x, wall[0].min.x);
+    }
+
+    #[test]
+    fn stop_at_wall_below() {
+        let wall = [tile(0., -60.)];
+
+        let translation = move_and_slide(START, Vec2::new(0., -50.), &wall);
+
+        assert_eq!(feet_rect(translation).min.y, wall[0].max.y);
+        assert_eq!(translation.x, 0.);
+    }
+
+    #[test]
+    fn slide_along_wall() {
+        let wall = [tile(100., 0.), tile(100., 48.), tile(100., 96.)];
+
+        let translation = move_and_slide(START, Vec2::new(100., 40.), &wall);
+
+        // Blocked horizontally, but still moving up along the wall
+        assert_eq!(translation, Vec3::new(64., 58., 10.));
+    }
+
+    #[test]
+    fn slide_rect_collides_with_the_whole_box() {
+        let rect = Rect::from_center_size(Vec2::ZERO, Vec2::splat(32.));
+        let wall = [tile(0., 60.)];
+
+        let moved = slide_rect(rect, Vec2::new(5., 30.), &wall);
+
+        // Unlike feet, the top of the box stops against the wall above
+        assert_eq!(moved.max.y, wall[0].min.y);
+        assert_eq!(moved.center().x, 5.);
+    }
+}